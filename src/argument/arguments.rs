@@ -1,6 +1,10 @@
 use std::collections::HashSet;
 
 use crate::argument::types::ArgumentType;
+use crate::argument::types::Arity;
+use crate::argument::types::RepeatPolicy;
+use crate::argument::validators::Validator;
+use crate::Value;
 
 #[derive(Debug, Clone, Default)]
 pub struct Argument {
@@ -8,11 +12,24 @@ pub struct Argument {
     pub invoke_with: HashSet<String>,
     pub required: bool,
     argument_type: ArgumentType,
+    index: Option<usize>,
+    arity: Arity,
+    help: String,
+    value_name: String,
+    default_value: Option<Value>,
+    env: Option<String>,
+    possible_values: Vec<String>,
+    validator: Option<Validator>,
+    on_repeat: RepeatPolicy,
+    conflicts_with: Vec<String>,
+    requires: Vec<String>,
+    required_if: Vec<(String, Value)>,
+    required_unless: Vec<String>,
 }
 
 impl Argument {
     /// create a new blank argument with specified type  
-    /// accepted types are "flag" "word" and  "vector"  
+    /// accepted types are "flag" "word" "vector" "positional" and "count"  
     pub fn with_type(argument_type: &str) -> Argument {
         Argument {
             argument_type: ArgumentType::from(argument_type),
@@ -22,12 +39,15 @@ impl Argument {
     /// give a name to the argument  
     /// this name will be used to access the parsed argument
     /// name must be unique for each argument
+    /// positional arguments are not invoked, so their name is not used as an invocator
     pub fn name(mut self, name: &str) -> Argument {
         if !self.name.is_empty() {
             panic!("name already specified")
         };
         self.name = name.to_string();
-        self.invoke_with.insert(name.to_string());
+        if !self.is_positional() {
+            self.invoke_with.insert(name.to_string());
+        }
         self
     }
     /// invoactor is the keyword that will be looked for when parsing the arguments  
@@ -42,6 +62,81 @@ impl Argument {
         self.required = required;
         self
     }
+    /// position of a positional argument among the other positionals  
+    /// defaults to the order in which positionals are added to the parser
+    pub fn index(mut self, index: usize) -> Argument {
+        self.index = Some(index);
+        self
+    }
+    /// number of values a positional argument takes, exactly one by default
+    pub fn arity(mut self, arity: Arity) -> Argument {
+        self.arity = arity;
+        self
+    }
+    /// description of the argument shown in the help text
+    pub fn help(mut self, help: &str) -> Argument {
+        self.help = help.to_string();
+        self
+    }
+    /// placeholder shown in the help text for the value of a word or vector argument  
+    /// defaults to the upper cased name
+    pub fn value_name(mut self, value_name: &str) -> Argument {
+        self.value_name = value_name.to_string();
+        self
+    }
+    /// value used when an optional argument is not given  
+    /// the value must match the type of the argument, this is checked when it is added to the parser
+    pub fn default_value(mut self, value: impl Into<Value>) -> Argument {
+        self.default_value = Some(value.into());
+        self
+    }
+    /// environment variable the value is read from when the argument is not given
+    pub fn env(mut self, variable: &str) -> Argument {
+        self.env = Some(variable.to_string());
+        self
+    }
+    /// restrict the values of a word or vector argument to the given choices
+    pub fn possible_values(mut self, values: &[&str]) -> Argument {
+        self.possible_values = values.iter().map(|f| f.to_string()).collect();
+        self
+    }
+    /// check every value of a word or vector argument with the given function  
+    /// the function returns the reason a value is rejected
+    pub fn validator<F>(mut self, validator: F) -> Argument
+    where
+        F: Fn(&str) -> Result<(), String> + Send + Sync + std::panic::RefUnwindSafe + 'static,
+    {
+        self.validator = Some(Validator::new(validator));
+        self
+    }
+    /// what to do when the argument is given more than once, the last value wins by default  
+    /// appending collects the values of every occurrence into one vector,
+    /// counts always add up their occurrences
+    pub fn on_repeat(mut self, policy: RepeatPolicy) -> Argument {
+        self.on_repeat = policy;
+        self
+    }
+    /// the argument cannot be given together with the named argument
+    pub fn conflicts_with(mut self, name: &str) -> Argument {
+        self.conflicts_with.push(name.to_string());
+        self
+    }
+    /// when the argument is given the named argument must be given too
+    pub fn requires(mut self, name: &str) -> Argument {
+        self.requires.push(name.to_string());
+        self
+    }
+    /// the argument must be given when the named argument has the given value
+    pub fn required_if(mut self, name: &str, value: impl Into<Value>) -> Argument {
+        self.required_if.push((name.to_string(), value.into()));
+        self
+    }
+    /// the argument must be given unless the named argument is given  
+    /// with several names, any one of them is enough
+    pub fn required_unless(mut self, name: &str) -> Argument {
+        self.required_unless.push(name.to_string());
+        self
+    }
     /// compare the given string and returns true if it matches any of the argument's invocators  
     /// the string should be either the name or one of the invocators  
     pub fn same_as(&self, identity: &str) -> bool {
@@ -51,9 +146,126 @@ impl Argument {
     pub fn is_valid(&self) -> bool {
         !self.name.is_empty()
     }
+    /// all invocators of the argument, sorted  
+    /// short invocators like `-o` come first, then long ones like `--oranges`, then bare words
+    pub fn invocators(&self) -> Vec<&str> {
+        let mut invocators = self
+            .invoke_with
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        invocators.sort_unstable_by_key(|f| {
+            let rank = if f.starts_with("--") {
+                1u8
+            } else if f.starts_with('-') {
+                0u8
+            } else {
+                2u8
+            };
+            (rank, *f)
+        });
+        invocators
+    }
     pub fn get_type(&self) -> &str {
         self.argument_type.as_ref()
     }
+    pub fn get_index(&self) -> Option<usize> {
+        self.index
+    }
+    pub fn get_arity(&self) -> Arity {
+        self.arity
+    }
+    pub fn get_help(&self) -> &str {
+        &self.help
+    }
+    pub fn get_value_name(&self) -> String {
+        if self.value_name.is_empty() {
+            self.name
+                .trim_start_matches('-')
+                .replace('-', "_")
+                .to_uppercase()
+        } else {
+            self.value_name.clone()
+        }
+    }
+    pub fn get_default_value(&self) -> Option<&Value> {
+        self.default_value.as_ref()
+    }
+    pub fn get_env(&self) -> Option<&str> {
+        self.env.as_deref()
+    }
+    pub fn get_possible_values(&self) -> &[String] {
+        &self.possible_values
+    }
+    pub fn get_validator(&self) -> Option<&Validator> {
+        self.validator.as_ref()
+    }
+    pub fn get_repeat_policy(&self) -> RepeatPolicy {
+        self.on_repeat
+    }
+    pub fn get_conflicts(&self) -> &[String] {
+        &self.conflicts_with
+    }
+    pub fn get_requires(&self) -> &[String] {
+        &self.requires
+    }
+    pub fn get_required_if(&self) -> &[(String, Value)] {
+        &self.required_if
+    }
+    pub fn get_required_unless(&self) -> &[String] {
+        &self.required_unless
+    }
+    /// names of every argument this one refers to
+    pub fn related(&self) -> Vec<&str> {
+        self.conflicts_with
+            .iter()
+            .chain(self.requires.iter())
+            .chain(self.required_if.iter().map(|f| &f.0))
+            .chain(self.required_unless.iter())
+            .map(String::as_str)
+            .collect()
+    }
+    /// true if the value is of the variant this argument produces when parsed
+    pub fn accepts_value(&self, value: &Value) -> bool {
+        match (self.argument_type, value) {
+            (ArgumentType::Flag, Value::Flag(_))
+            | (ArgumentType::Word, Value::Word(_))
+            | (ArgumentType::Vector, Value::Vector(_))
+            | (ArgumentType::Count, Value::Count(_)) => true,
+            (ArgumentType::Flag, Value::Vector(_)) | (ArgumentType::Word, Value::Vector(_)) => {
+                self.on_repeat == RepeatPolicy::Append
+            }
+            (ArgumentType::Positional, Value::Word(_)) => self.arity != Arity::OneOrMore,
+            (ArgumentType::Positional, Value::Vector(_)) => self.arity == Arity::OneOrMore,
+            _ => false,
+        }
+    }
+    /// appending arguments always hold a vector, other values are returned as they are
+    pub fn normalize_value(&self, value: Value) -> Value {
+        match (self.on_repeat, self.argument_type, value) {
+            (RepeatPolicy::Append, ArgumentType::Flag, value)
+            | (RepeatPolicy::Append, ArgumentType::Word, value) => Value::from(value.into_vec()),
+            (_, _, value) => value,
+        }
+    }
+    /// word and vector arguments take values, flags and counts only need to be present
+    pub fn takes_value(&self) -> bool {
+        matches!(
+            self.argument_type,
+            ArgumentType::Word | ArgumentType::Vector
+        )
+    }
+    /// positionals are required unless their arity allows no value
+    pub fn is_required(&self) -> bool {
+        if self.is_positional() {
+            self.arity.minimum() > 0
+        } else {
+            self.required
+        }
+    }
+    pub fn is_positional(&self) -> bool {
+        matches!(self.argument_type, ArgumentType::Positional)
+    }
 }
 
 mod argument_tests {
@@ -72,6 +284,51 @@ mod argument_tests {
         assert!(oranges.same_as("set-orange"));
     }
     #[test]
+    fn invocators_order() {
+        let oranges = super::Argument::with_type("flag")
+            .name("oranges")
+            .invoke_with("set-orange")
+            .invoke_with("--oranges")
+            .invoke_with("-o");
+        assert_eq!(
+            oranges.invocators(),
+            vec!["-o", "--oranges", "oranges", "set-orange"]
+        );
+    }
+    #[test]
+    fn value_name() {
+        let name = super::Argument::with_type("word").name("--my-name");
+        assert_eq!(name.get_value_name(), "MY_NAME");
+        let name = name.value_name("NAME").help("who you are");
+        assert_eq!(name.get_value_name(), "NAME");
+        assert_eq!(name.get_help(), "who you are");
+    }
+    #[test]
+    fn positional() {
+        let source = super::Argument::with_type("positional")
+            .name("source")
+            .index(1)
+            .arity(super::Arity::OneOrMore);
+        assert!(source.is_valid());
+        assert!(source.is_positional());
+        assert!(!source.same_as("source"));
+        assert_eq!(source.get_index(), Some(1));
+        assert_eq!(source.get_arity(), super::Arity::OneOrMore);
+    }
+    #[test]
+    fn accepts_value() {
+        let colors = super::Argument::with_type("vector")
+            .name("colors")
+            .default_value(vec!["red", "blue"]);
+        assert!(colors.accepts_value(colors.get_default_value().unwrap()));
+        assert!(!colors.accepts_value(&super::Value::from("red")));
+        let output = super::Argument::with_type("positional")
+            .name("output")
+            .arity(super::Arity::Optional);
+        assert!(output.accepts_value(&super::Value::from("out/")));
+        assert!(!output.accepts_value(&super::Value::from(true)));
+    }
+    #[test]
     #[should_panic]
     fn invalid_panic() {
         let apples = super::Argument::with_type("vector");
@@ -0,0 +1,59 @@
+/// a named set of arguments that is checked as a whole after parsing  
+/// an exclusive group allows at most one of its arguments, a required group at least one
+#[derive(Debug, Clone, Default)]
+pub struct ArgGroup {
+    pub name: String,
+    arguments: Vec<String>,
+    exclusive: bool,
+    required: bool,
+}
+
+impl ArgGroup {
+    pub fn new(name: &str) -> ArgGroup {
+        ArgGroup {
+            name: name.to_string(),
+            ..ArgGroup::default()
+        }
+    }
+    /// add arguments to the group by their names
+    pub fn args(mut self, names: &[&str]) -> ArgGroup {
+        self.arguments.extend(names.iter().map(|f| f.to_string()));
+        self
+    }
+    /// allow at most one argument of the group to be given
+    pub fn exclusive(mut self) -> ArgGroup {
+        self.exclusive = true;
+        self
+    }
+    /// require at least one argument of the group to be given
+    pub fn required(mut self) -> ArgGroup {
+        self.required = true;
+        self
+    }
+    pub fn get_arguments(&self) -> &[String] {
+        &self.arguments
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        self.arguments.iter().any(|f| f == name)
+    }
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+}
+
+mod groups_tests {
+    #[test]
+    fn building() {
+        let output = super::ArgGroup::new("output")
+            .args(&["json", "yaml"])
+            .args(&["table"])
+            .exclusive();
+        assert_eq!(output.get_arguments(), &["json", "yaml", "table"]);
+        assert!(output.contains("yaml"));
+        assert!(output.is_exclusive());
+        assert!(!output.is_required());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod arguments;
+pub mod groups;
 pub mod types;
+pub mod validators;
@@ -3,42 +3,50 @@ pub enum ArgumentType {
     Flag,
     Word,
     Vector,
+    Positional,
+    Count,
 }
 
 /// from &str  
-/// accepted values are: "flag" "word" and "vector"
+/// accepted values are: "flag" "word" "vector" "positional" and "count"
 impl From<&str> for ArgumentType {
     fn from(s: &str) -> ArgumentType {
         match s {
             "flag" => ArgumentType::Flag,
             "word" => ArgumentType::Word,
             "vector" => ArgumentType::Vector,
+            "positional" => ArgumentType::Positional,
+            "count" => ArgumentType::Count,
             _ => panic!("{} :type not found", s),
         }
     }
 }
 
 /// from String  
-/// accepted values are: "flag" "word" and "vector"
+/// accepted values are: "flag" "word" "vector" "positional" and "count"
 impl From<String> for ArgumentType {
     fn from(s: String) -> ArgumentType {
         match s.as_str() {
             "flag" => ArgumentType::Flag,
             "word" => ArgumentType::Word,
             "vector" => ArgumentType::Vector,
+            "positional" => ArgumentType::Positional,
+            "count" => ArgumentType::Count,
             _ => panic!("{} :type not found", s),
         }
     }
 }
 
 /// from &String  
-/// accepted values are: "flag" "word" and "vector"
+/// accepted values are: "flag" "word" "vector" "positional" and "count"
 impl From<&String> for ArgumentType {
     fn from(s: &String) -> ArgumentType {
         match s.as_str() {
             "flag" => ArgumentType::Flag,
             "word" => ArgumentType::Word,
             "vector" => ArgumentType::Vector,
+            "positional" => ArgumentType::Positional,
+            "count" => ArgumentType::Count,
             _ => panic!("{} :type not found", s),
         }
     }
@@ -51,6 +59,8 @@ impl From<ArgumentType> for String {
             ArgumentType::Flag => "flag",
             ArgumentType::Word => "word",
             ArgumentType::Vector => "vector",
+            ArgumentType::Positional => "positional",
+            ArgumentType::Count => "count",
         }
         .to_string()
     }
@@ -62,6 +72,8 @@ impl AsRef<str> for ArgumentType {
             ArgumentType::Flag => "flag",
             ArgumentType::Word => "word",
             ArgumentType::Vector => "vector",
+            ArgumentType::Positional => "positional",
+            ArgumentType::Count => "count",
         }
     }
 }
@@ -78,3 +90,78 @@ impl Default for ArgumentType {
         ArgumentType::Flag
     }
 }
+
+/// how many values a positional argument takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    One,
+    Optional,
+    OneOrMore,
+}
+
+impl Arity {
+    /// least number of values the positional needs
+    pub fn minimum(&self) -> usize {
+        match self {
+            Arity::One | Arity::OneOrMore => 1,
+            Arity::Optional => 0,
+        }
+    }
+}
+
+impl AsRef<str> for Arity {
+    fn as_ref(&self) -> &str {
+        match self {
+            Arity::One => "one",
+            Arity::Optional => "optional",
+            Arity::OneOrMore => "one-or-more",
+        }
+    }
+}
+
+impl std::fmt::Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+/// default is exactly one value
+impl Default for Arity {
+    fn default() -> Arity {
+        Arity::One
+    }
+}
+
+/// what happens when an argument is given more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatPolicy {
+    /// the last value replaces the earlier ones
+    LastWins,
+    /// every value is collected into one vector
+    Append,
+    /// giving the argument again is an error
+    Error,
+}
+
+impl AsRef<str> for RepeatPolicy {
+    fn as_ref(&self) -> &str {
+        match self {
+            RepeatPolicy::LastWins => "last-wins",
+            RepeatPolicy::Append => "append",
+            RepeatPolicy::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for RepeatPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+/// default is the last value winning
+impl Default for RepeatPolicy {
+    fn default() -> RepeatPolicy {
+        RepeatPolicy::LastWins
+    }
+}
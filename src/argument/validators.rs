@@ -0,0 +1,29 @@
+use std::fmt;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+
+type ValidatorFn = dyn Fn(&str) -> Result<(), String> + Send + Sync + RefUnwindSafe;
+
+/// a check run on every value given to an argument  
+/// it returns the reason the value was rejected as the error
+#[derive(Clone)]
+pub struct Validator(Arc<ValidatorFn>);
+
+impl Validator {
+    pub fn new<F>(validator: F) -> Validator
+    where
+        F: Fn(&str) -> Result<(), String> + Send + Sync + RefUnwindSafe + 'static,
+    {
+        Validator(Arc::new(validator))
+    }
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        (self.0)(value)
+    }
+}
+
+/// closures cannot be printed, so only the presence of the validator is shown
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Validator(..)")
+    }
+}
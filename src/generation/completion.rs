@@ -0,0 +1,391 @@
+use crate::Argument;
+use crate::Parser;
+
+/// shells that completion scripts can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl AsRef<str> for Shell {
+    fn as_ref(&self) -> &str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+impl std::fmt::Display for Shell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl Parser {
+    /// a completion script for the given shell
+    /// flags complete on their own, word and vector arguments complete their possible values
+    /// or file names, subcommands complete their own arguments
+    pub fn generate_completion(&self, shell: Shell, program_name: &str) -> String {
+        match shell {
+            Shell::Bash => self.bash_completion(program_name),
+            Shell::Zsh => self.zsh_completion(program_name),
+            Shell::Fish => self.fish_completion(program_name),
+        }
+    }
+    /// every parser reachable from this one with the subcommand path leading to it
+    fn contexts(&self, path: Vec<String>) -> Vec<(Vec<String>, &Parser)> {
+        let mut contexts = vec![(path.clone(), self)];
+        for name in &self.subcommand_order {
+            let mut sub_path = path.clone();
+            sub_path.push(name.clone());
+            contexts.extend(self.subcommands[name].contexts(sub_path));
+        }
+        contexts
+    }
+    /// options in declaration order, positionals have no invocators and are left out
+    fn options(&self) -> Vec<&Argument> {
+        self.order
+            .iter()
+            .map(|f| &self.arguments[f])
+            .filter(|f| !f.is_positional())
+            .collect()
+    }
+    fn bash_completion(&self, program_name: &str) -> String {
+        let function = function_name(program_name, &[]);
+        let contexts = self.contexts(Vec::new());
+        let mut script = format!("{}() {{\n", function);
+        script.push_str("    local cur prev context word i\n");
+        script.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+        script.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+        script.push_str(&format!("    context=\"{}\"\n", function));
+        if contexts.len() > 1 {
+            script.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+            script.push_str("        word=\"${COMP_WORDS[i]}\"\n");
+            script.push_str("        case \"$context:$word\" in\n");
+            for (path, _) in contexts.iter().skip(1) {
+                let (name, parent) = path.split_last().unwrap();
+                script.push_str(&format!(
+                    "            \"{}:{}\") context=\"{}\" ;;\n",
+                    function_name(program_name, parent),
+                    name,
+                    function_name(program_name, path)
+                ));
+            }
+            script.push_str("        esac\n");
+            script.push_str("    done\n");
+        }
+        script.push_str("    case \"$context\" in\n");
+        for (path, parser) in &contexts {
+            script.push_str(&format!("        {})\n", function_name(program_name, path)));
+            let takes_value = parser
+                .options()
+                .into_iter()
+                .filter(|f| f.takes_value())
+                .collect::<Vec<_>>();
+            if !takes_value.is_empty() {
+                script.push_str("            case \"$prev\" in\n");
+                for argument in takes_value {
+                    let values = if argument.get_possible_values().is_empty() {
+                        "-f".to_string()
+                    } else {
+                        format!("-W \"{}\"", argument.get_possible_values().join(" "))
+                    };
+                    script.push_str(&format!(
+                        "                {})\n                    COMPREPLY=( $(compgen {} -- \"$cur\") )\n                    return 0\n                    ;;\n",
+                        argument.invocators().join("|"),
+                        values
+                    ));
+                }
+                script.push_str("            esac\n");
+            }
+            let mut words = parser.invocators.iter().cloned().collect::<Vec<_>>();
+            words.sort();
+            words.extend(parser.subcommand_order.iter().cloned());
+            let files = if parser.positionals.is_empty() {
+                ""
+            } else {
+                " -f"
+            };
+            script.push_str(&format!(
+                "            COMPREPLY=( $(compgen{} -W \"{}\" -- \"$cur\") )\n            ;;\n",
+                files,
+                words.join(" ")
+            ));
+        }
+        script.push_str("    esac\n");
+        script.push_str("}\n");
+        script.push_str(&format!("complete -F {} {}\n", function, program_name));
+        script
+    }
+    fn zsh_completion(&self, program_name: &str) -> String {
+        let mut script = format!("#compdef {}\n", program_name);
+        for (path, parser) in self.contexts(Vec::new()) {
+            script.push('\n');
+            script.push_str(&format!("{}() {{\n", function_name(program_name, &path)));
+            script.push_str("    local state line\n");
+            script.push_str("    _arguments -C");
+            let mut bare = Vec::new();
+            for argument in parser.options() {
+                let help = zsh_escape(argument.get_help());
+                let value = match argument.get_type() {
+                    "word" | "vector" => {
+                        let action = if argument.get_possible_values().is_empty() {
+                            "_files".to_string()
+                        } else {
+                            format!("({})", argument.get_possible_values().join(" "))
+                        };
+                        format!(":{}:{}", zsh_escape(&argument.get_value_name()), action)
+                    }
+                    _ => String::new(),
+                };
+                let repeat = if argument.get_type().eq("vector") {
+                    "*"
+                } else {
+                    ""
+                };
+                let invocators = Parser::all_invocators(argument);
+                for invocator in invocators {
+                    if invocator.starts_with('-') {
+                        script.push_str(&format!(
+                            " \\\n        '{}{}[{}]{}'",
+                            repeat, invocator, help, value
+                        ));
+                    } else {
+                        bare.push(invocator);
+                    }
+                }
+            }
+            let mut words = parser.subcommand_order.clone();
+            words.extend(bare);
+            if words.is_empty() && parser.positionals.is_empty() {
+                script.push('\n');
+            } else {
+                script.push_str(" \\\n        '*:: :->args'\n");
+                script.push_str("    case $state in\n");
+                script.push_str("        args)\n");
+                script.push_str("            case $words[1] in\n");
+                for name in &parser.subcommand_order {
+                    let mut sub_path = path.clone();
+                    sub_path.push(name.clone());
+                    script.push_str(&format!(
+                        "                {}) {} ;;\n",
+                        name,
+                        function_name(program_name, &sub_path)
+                    ));
+                }
+                let mut fallback = Vec::new();
+                if !words.is_empty() {
+                    fallback.push(format!("compadd -- {}", words.join(" ")));
+                }
+                if !parser.positionals.is_empty() {
+                    fallback.push("_files".to_string());
+                }
+                script.push_str(&format!("                *) {} ;;\n", fallback.join("; ")));
+                script.push_str("            esac\n");
+                script.push_str("            ;;\n");
+                script.push_str("    esac\n");
+            }
+            script.push_str("}\n");
+        }
+        script.push('\n');
+        script.push_str(&format!("{} \"$@\"\n", function_name(program_name, &[])));
+        script
+    }
+    fn fish_completion(&self, program_name: &str) -> String {
+        let mut script = String::new();
+        for (path, parser) in self.contexts(Vec::new()) {
+            let condition = match path.split_last() {
+                Some((name, _)) => format!(" -n '__fish_seen_subcommand_from {}'", name),
+                None if !parser.subcommands.is_empty() => {
+                    format!(
+                        " -n 'not __fish_seen_subcommand_from {}'",
+                        parser.subcommand_order.join(" ")
+                    )
+                }
+                None => String::new(),
+            };
+            for name in &parser.subcommand_order {
+                script.push_str(&format!(
+                    "complete -c {}{} -f -a '{}' -d '{}'\n",
+                    program_name,
+                    condition,
+                    name,
+                    fish_escape(&parser.subcommands[name].about)
+                ));
+            }
+            for argument in parser.options() {
+                let invocators = Parser::all_invocators(argument);
+                let value = match argument.get_type() {
+                    "word" | "vector" if argument.get_possible_values().is_empty() => {
+                        " -r -F".to_string()
+                    }
+                    "word" | "vector" => format!(
+                        " -x -a '{}'",
+                        fish_escape(&argument.get_possible_values().join(" "))
+                    ),
+                    _ => String::new(),
+                };
+                let help = if argument.get_help().is_empty() {
+                    String::new()
+                } else {
+                    format!(" -d '{}'", fish_escape(argument.get_help()))
+                };
+                for invocator in invocators {
+                    let option = if let Some(long) = invocator.strip_prefix("--") {
+                        format!(" -l {}{}", long, value)
+                    } else if let Some(short) = invocator.strip_prefix('-') {
+                        if short.chars().count() == 1 {
+                            format!(" -s {}{}", short, value)
+                        } else {
+                            format!(" -o {}{}", short, value)
+                        }
+                    } else {
+                        format!(" -f -a '{}'", invocator)
+                    };
+                    script.push_str(&format!(
+                        "complete -c {}{}{}{}\n",
+                        program_name, condition, option, help
+                    ));
+                }
+            }
+        }
+        script
+    }
+}
+
+/// `_program` for the top level, `_program_sub_command` for subcommands
+fn function_name(program_name: &str, path: &[String]) -> String {
+    let mut name = format!("_{}", program_name);
+    for item in path {
+        name.push('_');
+        name.push_str(item);
+    }
+    name.chars()
+        .map(|f| if f.is_ascii_alphanumeric() { f } else { '_' })
+        .collect()
+}
+
+/// escape text placed inside single quotes and the brackets of an `_arguments` spec
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+mod completion_tests {
+    #[test]
+    fn bash() {
+        let mut migrate = super::Parser::new();
+        migrate.add_argument(super::Argument::with_type("flag").name("--dry-run"));
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("sleep")
+                .invoke_with("--sleep")
+                .invoke_with("-s"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("level")
+                .invoke_with("--level")
+                .possible_values(&["debug", "info"]),
+        );
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("--files")
+                .invoke_with("-f"),
+        );
+        parser.add_subcommand("migrate", migrate);
+        let script = parser.generate_completion(super::Shell::Bash, "dragons");
+        assert!(script.starts_with("_dragons() {\n"));
+        assert!(script.contains("\"_dragons:migrate\") context=\"_dragons_migrate\" ;;"));
+        assert!(script.contains(
+            "                --level|level)\n                    COMPREPLY=( $(compgen -W \"debug info\" -- \"$cur\") )"
+        ));
+        assert!(script.contains("                -f|--files)\n                    COMPREPLY=( $(compgen -f -- \"$cur\") )"));
+        assert!(script.contains(
+            "COMPREPLY=( $(compgen -W \"--files --level --no-sleep --sleep -f -no-s -s level no-sleep sleep migrate\" -- \"$cur\") )"
+        ));
+        assert!(
+            script.contains("COMPREPLY=( $(compgen -W \"--dry-run --no-dry-run\" -- \"$cur\") )")
+        );
+        assert!(script.ends_with("complete -F _dragons dragons\n"));
+    }
+    #[test]
+    fn zsh() {
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("sleep")
+                .invoke_with("-s")
+                .help("put the dragons to sleep"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--level")
+                .possible_values(&["debug", "info"]),
+        );
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("--files")
+                .invoke_with("-f"),
+        );
+        parser.add_subcommand("hatch", super::Parser::new());
+        let script = parser.generate_completion(super::Shell::Zsh, "dragons");
+        assert!(script.starts_with("#compdef dragons\n"));
+        assert!(script.contains("'-s[put the dragons to sleep]'"));
+        assert!(script.contains("'-no-s[put the dragons to sleep]'"));
+        assert!(script.contains("'--level[]:LEVEL:(debug info)'"));
+        assert!(script.contains("'*-f[]:FILES:_files'"));
+        assert!(script.contains("                hatch) _dragons_hatch ;;"));
+        assert!(script.contains("                *) compadd -- hatch sleep no-sleep ;;"));
+        assert!(script.contains("_dragons_hatch() {\n"));
+        assert!(script.ends_with("_dragons \"$@\"\n"));
+    }
+    #[test]
+    fn fish() {
+        let mut feed = super::Parser::new().about("feed the dragons");
+        feed.add_argument(super::Argument::with_type("word").name("--food"));
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(
+            super::Argument::with_type("count")
+                .name("-v")
+                .help("more output"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("--color")
+                .possible_values(&["red", "green"]),
+        );
+        parser.add_subcommand("feed", feed);
+        let script = parser.generate_completion(super::Shell::Fish, "dragons");
+        let root = " -n 'not __fish_seen_subcommand_from feed'";
+        assert!(script.contains(&format!(
+            "complete -c dragons{} -f -a 'feed' -d 'feed the dragons'\n",
+            root
+        )));
+        assert!(script.contains(&format!(
+            "complete -c dragons{} -s v -d 'more output'\n",
+            root
+        )));
+        assert!(script.contains(&format!(
+            "complete -c dragons{} -o no-v -d 'more output'\n",
+            root
+        )));
+        assert!(script.contains(&format!(
+            "complete -c dragons{} -l color -x -a 'red green'\n",
+            root
+        )));
+        assert!(script
+            .contains("complete -c dragons -n '__fish_seen_subcommand_from feed' -l food -r -F\n"));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::ArgGroup;
+use crate::Argument;
+use crate::Arity;
+use crate::Parser;
+
+const DEFAULT_WIDTH: usize = 80;
+const MAX_COLUMN: usize = 30;
+const MIN_TEXT_WIDTH: usize = 20;
+
+impl Parser {
+    /// the single usage line, listing required options, positionals and subcommands
+    pub fn render_usage(&self) -> String {
+        let mut usage = vec!["usage:".to_string()];
+        if !self.program.is_empty() {
+            usage.push(self.program.clone());
+        }
+        let options = self
+            .order
+            .iter()
+            .map(|f| &self.arguments[f])
+            .filter(|f| !f.is_positional())
+            .collect::<Vec<_>>();
+        if options.iter().any(|f| !f.required) {
+            usage.push("[options]".to_string());
+        }
+        for argument in options.iter().filter(|f| f.required) {
+            let invocators = argument.invocators();
+            let invocator = invocators
+                .iter()
+                .find(|f| f.starts_with("--"))
+                .or_else(|| invocators.first())
+                .copied()
+                .unwrap_or_default();
+            usage.push(format!("{}{}", invocator, value_placeholder(argument)));
+        }
+        for name in &self.positionals {
+            usage.push(positional_placeholder(&self.arguments[name]));
+        }
+        if !self.subcommands.is_empty() {
+            usage.push("<subcommand>".to_string());
+        }
+        usage.join(" ")
+    }
+    /// the full help text: usage, description, and a table for positionals, options and subcommands
+    /// every invocator is listed, including the negated forms of flags
+    pub fn render_help(&self) -> String {
+        let width = if self.help_width == 0 {
+            DEFAULT_WIDTH
+        } else {
+            self.help_width
+        };
+        let mut help = self.render_usage();
+        help.push('\n');
+        if !self.about.is_empty() {
+            help.push('\n');
+            for line in wrap(&self.about, width) {
+                help.push_str(&line);
+                help.push('\n');
+            }
+        }
+        let positionals = self
+            .positionals
+            .iter()
+            .map(|f| &self.arguments[f])
+            .map(|f| (positional_placeholder(f), help_column(f)))
+            .collect::<Vec<_>>();
+        let options = self
+            .order
+            .iter()
+            .map(|f| &self.arguments[f])
+            .filter(|f| !f.is_positional() && !self.groups.iter().any(|g| g.contains(&f.name)))
+            .map(|f| (invocator_column(f), help_column(f)))
+            .collect::<Vec<_>>();
+        let subcommands = self
+            .subcommand_order
+            .iter()
+            .map(|f| (f.clone(), self.subcommands[f].about.clone()))
+            .collect::<Vec<_>>();
+        let mut sections = vec![
+            ("arguments".to_string(), positionals),
+            ("options".to_string(), options),
+        ];
+        for group in &self.groups {
+            let rows = group
+                .get_arguments()
+                .iter()
+                .map(|f| &self.arguments[f])
+                .map(|f| (invocator_column(f), help_column(f)))
+                .collect::<Vec<_>>();
+            sections.push((group_title(group), rows));
+        }
+        sections.push(("subcommands".to_string(), subcommands));
+        let column = sections
+            .iter()
+            .flat_map(|f| f.1.iter())
+            .map(|f| f.0.chars().count())
+            .filter(|f| *f <= MAX_COLUMN)
+            .max()
+            .unwrap_or(0);
+        for (title, rows) in &sections {
+            if !rows.is_empty() {
+                help.push('\n');
+                help.push_str(&render_table(title, rows, column, width));
+            }
+        }
+        help
+    }
+}
+
+/// the group name followed by the rules of the group, e.g. `output (exclusive, required)`
+fn group_title(group: &ArgGroup) -> String {
+    let mut rules = Vec::new();
+    if group.is_exclusive() {
+        rules.push("exclusive");
+    }
+    if group.is_required() {
+        rules.push("required");
+    }
+    if rules.is_empty() {
+        group.name.clone()
+    } else {
+        format!("{} ({})", group.name, rules.join(", "))
+    }
+}
+
+/// ` <VALUE>` for word arguments and ` <VALUE>...` for vector arguments
+pub(crate) fn value_placeholder(argument: &Argument) -> String {
+    match argument.get_type() {
+        "word" => format!(" <{}>", argument.get_value_name()),
+        "vector" => format!(" <{}>...", argument.get_value_name()),
+        _ => String::new(),
+    }
+}
+
+/// `<name>`, `[name]` or `<name>...` depending on the arity
+pub(crate) fn positional_placeholder(argument: &Argument) -> String {
+    let name = argument.get_value_name();
+    match argument.get_arity() {
+        Arity::One => format!("<{}>", name),
+        Arity::Optional => format!("[{}]", name),
+        Arity::OneOrMore => format!("<{}>...", name),
+    }
+}
+
+fn invocator_column(argument: &Argument) -> String {
+    let invocators = Parser::all_invocators(argument);
+    format!("{}{}", invocators.join(", "), value_placeholder(argument))
+}
+
+/// the help of the argument followed by its possible values and whether it is required or its default
+pub(crate) fn help_column(argument: &Argument) -> String {
+    let mut help = argument.get_help().to_string();
+    let choices = argument.get_possible_values();
+    if !choices.is_empty() {
+        if !help.is_empty() {
+            help.push(' ');
+        }
+        help.push_str(&format!("[possible values: {}]", choices.join(", ")));
+    }
+    let marker = if argument.required {
+        Some("(required)".to_string())
+    } else {
+        argument
+            .get_default_value()
+            .map(|f| format!("(default: {})", f))
+    };
+    if let Some(marker) = marker {
+        if !help.is_empty() {
+            help.push(' ');
+        }
+        help.push_str(&marker);
+    }
+    help
+}
+
+/// a titled two column table, the second column is wrapped to fit the width  
+/// entries too wide for the first column get their text on the next line
+fn render_table(title: &str, rows: &[(String, String)], column: usize, width: usize) -> String {
+    let indent = column + 4;
+    let text_width = width.saturating_sub(indent).max(MIN_TEXT_WIDTH);
+    let mut table = format!("{}:\n", title);
+    for (left, text) in rows {
+        let mut lines = wrap(text, text_width).into_iter();
+        if left.chars().count() > column {
+            table.push_str(&format!("  {}\n", left));
+        } else {
+            let first = lines.next().unwrap_or_default();
+            let line = format!("  {:<width$}  {}", left, first, width = column);
+            table.push_str(line.trim_end());
+            table.push('\n');
+        }
+        for line in lines {
+            table.push_str(&format!("{}{}\n", " ".repeat(indent), line));
+        }
+    }
+    table
+}
+
+/// greedy word wrap, words longer than the width are kept whole
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+mod help_tests {
+    #[test]
+    fn wrapping() {
+        let lines = super::wrap("the quick brown fox jumps over the lazy dog", 15);
+        assert_eq!(
+            lines,
+            vec!["the quick brown", "fox jumps over", "the lazy dog"]
+        );
+        assert!(super::wrap("   ", 10).is_empty());
+    }
+    #[test]
+    fn render_help() {
+        let mut parser = super::Parser::with_capacity(4)
+            .program_name("dragons")
+            .about("keeps track of dragons and their colors")
+            .help_width(60);
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("sleep")
+                .invoke_with("--sleep")
+                .invoke_with("-s")
+                .help("put every dragon to sleep before counting them"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("dragon-colors")
+                .invoke_with("-d")
+                .value_name("COLOR")
+                .required(true)
+                .help("colors of the dragons"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("positional")
+                .name("cave")
+                .help("where the dragons live"),
+        );
+        parser.add_subcommand("feed", super::Parser::new().about("feed the dragons"));
+        let help = parser.render_help();
+        let expected = "\
+usage: dragons [options] -d <COLOR>... <CAVE> <subcommand>
+
+keeps track of dragons and their colors
+
+arguments:
+  <CAVE>                        where the dragons live
+
+options:
+  -s, --sleep, sleep, -no-s, --no-sleep, no-sleep
+                                put every dragon to sleep
+                                before counting them
+  -d, dragon-colors <COLOR>...  colors of the dragons
+                                (required)
+
+subcommands:
+  feed                          feed the dragons
+";
+        assert_eq!(help, expected);
+    }
+    #[test]
+    fn group_heading() {
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(super::Argument::with_type("flag").name("--json"));
+        parser.add_argument(super::Argument::with_type("flag").name("--table"));
+        parser.add_argument(super::Argument::with_type("flag").name("--quiet"));
+        parser.add_group(
+            super::ArgGroup::new("output")
+                .args(&["--json", "--table"])
+                .exclusive(),
+        );
+        let expected = "\
+usage: [options]
+
+options:
+  --quiet, --no-quiet
+
+output (exclusive):
+  --json, --no-json
+  --table, --no-table
+";
+        assert_eq!(parser.render_help(), expected);
+    }
+}
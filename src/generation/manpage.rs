@@ -0,0 +1,217 @@
+use crate::generation::help::help_column;
+use crate::generation::help::positional_placeholder;
+use crate::generation::help::value_placeholder;
+use crate::Argument;
+use crate::Parser;
+
+impl Parser {
+    /// a man page in roff with NAME, SYNOPSIS, DESCRIPTION, ARGUMENTS and OPTIONS sections
+    /// every invocator is listed with the type of the argument, including the negated forms of flags
+    /// subcommands get a section each under SUBCOMMANDS, named by their full path
+    /// `program_name` is given separately since pages are usually generated before the program runs
+    pub fn render_manpage(&self, program_name: &str, section: &str, date: &str) -> String {
+        assert!(!program_name.is_empty(), "program name cannot be empty");
+        let mut page = format!(
+            ".TH \"{}\" \"{}\" \"{}\"\n",
+            escape(&program_name.to_uppercase()),
+            escape(section),
+            escape(date)
+        );
+        page.push_str(".SH NAME\n");
+        if self.about.is_empty() {
+            page.push_str(&format!("{}\n", escape(program_name)));
+        } else {
+            page.push_str(&format!(
+                "{} \\- {}\n",
+                escape(program_name),
+                escape(&self.about)
+            ));
+        }
+        page.push_str(".SH SYNOPSIS\n");
+        page.push_str(&synopsis(self, program_name));
+        if !self.about.is_empty() {
+            page.push_str(".SH DESCRIPTION\n");
+            page.push_str(&format!("{}\n", escape(&self.about)));
+        }
+        page.push_str(&entries(self, ".SH"));
+        if !self.subcommand_order.is_empty() {
+            page.push_str(".SH SUBCOMMANDS\n");
+            subcommand_sections(self, program_name, &mut page);
+        }
+        page
+    }
+}
+
+/// a `.SS` section for every subcommand, followed by the sections of its own subcommands
+fn subcommand_sections(parser: &Parser, path: &str, page: &mut String) {
+    for name in &parser.subcommand_order {
+        let subcommand = &parser.subcommands[name];
+        let path = format!("{} {}", path, name);
+        page.push_str(&format!(".SS \"{}\"\n", escape(&path)));
+        if !subcommand.about.is_empty() {
+            page.push_str(&format!("{}\n.PP\n", escape(&subcommand.about)));
+        }
+        page.push_str(&synopsis(subcommand, &path));
+        page.push_str(&entries(subcommand, ".PP\n.B"));
+        subcommand_sections(subcommand, &path, page);
+    }
+}
+
+/// the usage line with the program or subcommand path in bold
+fn synopsis(parser: &Parser, path: &str) -> String {
+    let usage = parser.render_usage();
+    let usage = usage.strip_prefix("usage:").unwrap_or(&usage).trim_start();
+    let usage = usage
+        .strip_prefix(&parser.program)
+        .unwrap_or(usage)
+        .trim_start();
+    let mut line = format!("\\fB{}\\fR", escape(path));
+    if !usage.is_empty() {
+        line.push(' ');
+        line.push_str(&escape(usage));
+    }
+    line.push('\n');
+    line
+}
+
+/// the positionals under ARGUMENTS and the other arguments under OPTIONS,
+/// `heading` is the request the titles are written with
+fn entries(parser: &Parser, heading: &str) -> String {
+    let mut text = String::new();
+    if !parser.positionals.is_empty() {
+        text.push_str(&format!("{} ARGUMENTS\n", heading));
+        for name in &parser.positionals {
+            let argument = &parser.arguments[name];
+            let tag = format!("\\fI{}\\fR", escape(&positional_placeholder(argument)));
+            text.push_str(&entry(&tag, argument));
+        }
+    }
+    let options = parser
+        .order
+        .iter()
+        .map(|f| &parser.arguments[f])
+        .filter(|f| !f.is_positional())
+        .collect::<Vec<_>>();
+    if !options.is_empty() {
+        text.push_str(&format!("{} OPTIONS\n", heading));
+        for argument in options {
+            text.push_str(&entry(&option_tag(argument), argument));
+        }
+    }
+    text
+}
+
+/// every invocator in bold, flags and counts followed by their negated forms,
+/// then the value placeholder and the type
+fn option_tag(argument: &Argument) -> String {
+    let invocators = Parser::all_invocators(argument);
+    let mut tag = invocators
+        .iter()
+        .map(|f| format!("\\fB{}\\fR", escape(f)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholder = value_placeholder(argument);
+    if !placeholder.is_empty() {
+        tag.push_str(&format!(" \\fI{}\\fR", escape(placeholder.trim_start())));
+    }
+    tag.push_str(&format!(" ({})", argument.get_type()));
+    tag
+}
+
+fn entry(tag: &str, argument: &Argument) -> String {
+    let mut text = format!(".TP\n{}\n", tag);
+    let help = help_column(argument);
+    if !help.is_empty() {
+        text.push_str(&escape(&help));
+        text.push('\n');
+    }
+    text
+}
+
+/// escape backslashes and dashes, and keep lines from being read as requests
+fn escape(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+    escaped
+        .lines()
+        .map(|f| {
+            if f.starts_with('.') || f.starts_with('\'') {
+                format!("\\&{}", f)
+            } else {
+                f.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+mod manpage_tests {
+    #[test]
+    fn escaping() {
+        assert_eq!(super::escape("--no-sleep"), "\\-\\-no\\-sleep");
+        assert_eq!(super::escape("C:\\dir"), "C:\\edir");
+        assert_eq!(super::escape(".hidden\n'quoted"), "\\&.hidden\n\\&'quoted");
+    }
+    #[test]
+    fn render_manpage() {
+        let mut count = super::Parser::new().about("count the gold");
+        count.add_argument(
+            super::Argument::with_type("vector")
+                .name("--coins")
+                .value_name("COIN"),
+        );
+        let mut hoard = super::Parser::new();
+        hoard.add_subcommand("count", count);
+        let mut parser = super::Parser::with_capacity(3).about("keeps track of dragons");
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("--sleep")
+                .invoke_with("-s")
+                .help("put the dragons to sleep"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--level")
+                .default_value("info"),
+        );
+        parser.add_argument(super::Argument::with_type("positional").name("cave"));
+        parser.add_subcommand("hoard", hoard);
+        let expected = "\
+.TH \"DRAGONS\" \"1\" \"2024\\-01\\-31\"
+.SH NAME
+dragons \\- keeps track of dragons
+.SH SYNOPSIS
+\\fBdragons\\fR [options] <CAVE> <subcommand>
+.SH DESCRIPTION
+keeps track of dragons
+.SH ARGUMENTS
+.TP
+\\fI<CAVE>\\fR
+.SH OPTIONS
+.TP
+\\fB\\-s\\fR, \\fB\\-\\-sleep\\fR, \\fB\\-no\\-s\\fR, \\fB\\-\\-no\\-sleep\\fR (flag)
+put the dragons to sleep
+.TP
+\\fB\\-\\-level\\fR \\fI<LEVEL>\\fR (word)
+(default: info)
+.SH SUBCOMMANDS
+.SS \"dragons hoard\"
+\\fBdragons hoard\\fR <subcommand>
+.SS \"dragons hoard count\"
+count the gold
+.PP
+\\fBdragons hoard count\\fR [options]
+.PP
+.B OPTIONS
+.TP
+\\fB\\-\\-coins\\fR \\fI<COIN>...\\fR (vector)
+";
+        assert_eq!(
+            parser.render_manpage("dragons", "1", "2024-01-31"),
+            expected
+        );
+        let named = parser.program_name("named");
+        assert!(named
+            .render_manpage("dragons", "1", "2024-01-31")
+            .contains("\\fBdragons\\fR [options] <CAVE> <subcommand>"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod completion;
+pub mod help;
+pub mod manpage;
@@ -1,8 +1,20 @@
 pub mod argument;
+pub mod generation;
 pub mod parsing;
+pub mod spec;
 
 pub use argument::arguments::Argument;
+pub use argument::groups::ArgGroup;
 pub use argument::types::ArgumentType;
+pub use argument::types::Arity;
+pub use argument::types::RepeatPolicy;
+pub use argument::validators::Validator;
+pub use generation::completion::Shell;
+pub use parsing::config::ConfigFile;
+pub use parsing::errors::ConversionError;
+pub use parsing::errors::ParseError;
 pub use parsing::parsed_arguments::ParsedArguments;
 pub use parsing::parser::Parser;
+pub use parsing::sources::ValueSource;
 pub use parsing::values::Value;
+pub use spec::errors::SpecError;
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::ParseError;
+
+/// a `key = value` line of a configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// a simple INI file, keys are argument names and sections name subcommands
+/// keys before the first section belong to the top level parser, `[db.migrate]`
+/// belongs to the `migrate` subcommand of `db`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    sections: Vec<(String, usize)>,
+    entries: HashMap<String, Vec<ConfigEntry>>,
+}
+
+impl ConfigFile {
+    pub fn read(path: &Path) -> Result<ConfigFile, ParseError> {
+        let text = std::fs::read_to_string(path).map_err(|f| ParseError::ConfigRead {
+            path: path.to_path_buf(),
+            message: f.to_string(),
+        })?;
+        ConfigFile::parse(path, &text)
+    }
+    /// parse the text of a configuration file, `path` is only used for error messages
+    /// blank lines and lines starting with `#` or `;` are ignored,
+    /// values can be wrapped in double quotes to keep surrounding spaces
+    pub fn parse(path: &Path, text: &str) -> Result<ConfigFile, ParseError> {
+        let error = |line: usize, message: String| ParseError::Config {
+            path: path.to_path_buf(),
+            line,
+            message,
+        };
+        let mut config = ConfigFile {
+            path: path.to_path_buf(),
+            ..ConfigFile::default()
+        };
+        let mut section = String::new();
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = match name.strip_suffix(']') {
+                    Some(name) => name.trim(),
+                    None => return Err(error(number, "expected ] after section name".to_string())),
+                };
+                if name.is_empty() {
+                    return Err(error(number, "empty section name".to_string()));
+                }
+                if config.sections.iter().any(|f| f.0 == name) {
+                    return Err(error(number, format!("duplicate section: {}", name)));
+                }
+                section = name.to_string();
+                config.sections.push((section.clone(), number));
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(error(number, "expected key = value".to_string())),
+            };
+            if key.is_empty() {
+                return Err(error(number, "missing key before =".to_string()));
+            }
+            let value = match value.strip_prefix('"') {
+                Some(quoted) => match quoted.strip_suffix('"') {
+                    Some(unquoted) => unquoted,
+                    None => return Err(error(number, "unterminated quote".to_string())),
+                },
+                None => value,
+            };
+            let entries = config.entries.entry(section.clone()).or_default();
+            if entries.iter().any(|f| f.key == key) {
+                return Err(error(number, format!("duplicate key: {}", key)));
+            }
+            entries.push(ConfigEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+                line: number,
+            });
+        }
+        Ok(config)
+    }
+    /// sections in the order they appear with the line they start on,
+    /// the top level section has no header and is left out
+    pub fn sections(&self) -> &[(String, usize)] {
+        &self.sections
+    }
+    /// entries of a section, the top level section is the empty string
+    pub fn entries(&self, section: &str) -> &[ConfigEntry] {
+        self.entries.get(section).map_or(&[], Vec::as_slice)
+    }
+}
+
+mod config_tests {
+    #[test]
+    fn parsing() {
+        let text = "\
+# top level
+level = debug
+name = \" spaced \"
+
+[db.migrate]
+; dry runs only
+dry-run=yes
+";
+        let path = std::path::Path::new("app.ini");
+        let config = super::ConfigFile::parse(path, text).unwrap();
+        assert_eq!(config.sections(), &[("db.migrate".to_string(), 5)]);
+        assert_eq!(config.entries("")[0].key, "level");
+        assert_eq!(config.entries("")[1].value, " spaced ");
+        assert_eq!(
+            config.entries("db.migrate"),
+            &[super::ConfigEntry {
+                key: "dry-run".to_string(),
+                value: "yes".to_string(),
+                line: 7
+            }]
+        );
+        assert!(config.entries("missing").is_empty());
+    }
+    #[test]
+    fn syntax_errors() {
+        let path = std::path::Path::new("app.ini");
+        let error = super::ConfigFile::parse(path, "level = debug\nlevel\n").unwrap_err();
+        assert_eq!(error.to_string(), "app.ini:2: expected key = value");
+        let error = super::ConfigFile::parse(path, "[db\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "app.ini:1: expected ] after section name"
+        );
+        let error = super::ConfigFile::parse(path, "a = 1\na = 2\n").unwrap_err();
+        assert_eq!(error.to_string(), "app.ini:2: duplicate key: a");
+    }
+}
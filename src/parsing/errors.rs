@@ -0,0 +1,400 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// every way in which parsing a list of arguments can fail
+/// each variant carries the token that caused the failure  
+/// `suggestions` are the closest known words to a mistyped one, closest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// the token is neither an invocator nor a cluster of known flags
+    UnrecognizedArgument {
+        token: String,
+        suggestions: Vec<String>,
+    },
+    /// an abbreviated invocator fits more than one argument
+    AmbiguousArgument {
+        token: String,
+        candidates: Vec<String>,
+    },
+    /// a word argument was the last token and has no value
+    MissingValue { argument: String, token: String },
+    /// a word argument was followed by another argument instead of a value
+    UnexpectedArgument {
+        argument: String,
+        token: String,
+        found: String,
+    },
+    /// a flag was given a value with `=`, e.g. `--sleep=yes`
+    FlagWithValue { argument: String, token: String },
+    /// an argument that may only be given once was repeated
+    RepeatedArgument { argument: String, token: String },
+    /// a vector argument was not followed by any value
+    EmptyVector { argument: String, token: String },
+    /// a cluster of short arguments such as `-Mn` contains a non flag argument
+    NonFlagInCluster { argument: String, token: String },
+    /// a bare word was left over after every positional argument was filled,
+    /// suggestions are the subcommands it may have been meant as
+    UnexpectedPositional {
+        token: String,
+        suggestions: Vec<String>,
+    },
+    /// the environment variable of an argument holds a value that cannot be converted to its type
+    InvalidEnvironmentValue {
+        argument: String,
+        variable: String,
+        value: String,
+    },
+    /// a value is not one of the possible values of the argument
+    InvalidChoice {
+        argument: String,
+        value: String,
+        choices: Vec<String>,
+        suggestions: Vec<String>,
+    },
+    /// the validator of the argument rejected a value
+    InvalidValue {
+        argument: String,
+        value: String,
+        message: String,
+    },
+    /// two arguments that conflict with each other were both given
+    Conflict { argument: String, other: String },
+    /// an argument was given without an argument it requires
+    MissingDependency { argument: String, required: String },
+    /// an argument was not given although another argument has the value that requires it
+    MissingRequiredIf {
+        argument: String,
+        other: String,
+        value: String,
+    },
+    /// an argument was not given and neither was any of the arguments that make it optional
+    MissingRequiredUnless {
+        argument: String,
+        others: Vec<String>,
+    },
+    /// more than one argument of an exclusive group was given
+    GroupConflict {
+        group: String,
+        arguments: Vec<String>,
+    },
+    /// no argument of a required group was given
+    GroupMissing {
+        group: String,
+        arguments: Vec<String>,
+    },
+    /// the configuration file could not be read
+    ConfigRead { path: PathBuf, message: String },
+    /// a line of the configuration file is malformed, names an unknown argument or section,
+    /// or holds a value that cannot be converted to the type of its argument
+    Config {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// a response file given on the command line could not be read
+    ResponseFileRead { path: PathBuf, message: String },
+    /// a line of a response file has an unterminated quote or names a response file
+    /// that cannot be read, is nested too deep or includes itself
+    ResponseFile {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// required arguments that were not given, each with its invocators  
+    /// positional arguments have no invocators
+    MissingRequired { missing: Vec<(String, Vec<String>)> },
+    /// an error that happened while parsing the arguments of a subcommand  
+    /// `path` lists the subcommands from the outermost to the one that failed
+    Subcommand {
+        path: Vec<String>,
+        error: Box<ParseError>,
+    },
+}
+
+impl ParseError {
+    /// wrap an error coming from the subcommand `name`, extending the path if it is already wrapped
+    pub(crate) fn in_subcommand(self, name: &str) -> ParseError {
+        match self {
+            ParseError::Subcommand { mut path, error } => {
+                path.insert(0, name.to_string());
+                ParseError::Subcommand { path, error }
+            }
+            error => ParseError::Subcommand {
+                path: vec![name.to_string()],
+                error: Box::new(error),
+            },
+        }
+    }
+    /// name of the argument the error is about, if there is one  
+    /// for missing required arguments this is the first missing argument
+    pub fn argument(&self) -> Option<&str> {
+        match self {
+            ParseError::UnrecognizedArgument { .. }
+            | ParseError::UnexpectedPositional { .. }
+            | ParseError::AmbiguousArgument { .. }
+            | ParseError::ConfigRead { .. }
+            | ParseError::Config { .. }
+            | ParseError::ResponseFileRead { .. }
+            | ParseError::ResponseFile { .. } => None,
+            ParseError::MissingRequired { missing } => missing.first().map(|f| f.0.as_str()),
+            ParseError::Subcommand { error, .. } => error.argument(),
+            ParseError::MissingValue { argument, .. }
+            | ParseError::UnexpectedArgument { argument, .. }
+            | ParseError::EmptyVector { argument, .. }
+            | ParseError::FlagWithValue { argument, .. }
+            | ParseError::RepeatedArgument { argument, .. }
+            | ParseError::NonFlagInCluster { argument, .. }
+            | ParseError::InvalidEnvironmentValue { argument, .. }
+            | ParseError::InvalidChoice { argument, .. }
+            | ParseError::InvalidValue { argument, .. }
+            | ParseError::Conflict { argument, .. }
+            | ParseError::MissingDependency { argument, .. }
+            | ParseError::MissingRequiredIf { argument, .. }
+            | ParseError::MissingRequiredUnless { argument, .. } => Some(argument),
+            ParseError::GroupConflict { arguments, .. }
+            | ParseError::GroupMissing { arguments, .. } => arguments.first().map(String::as_str),
+        }
+    }
+    /// the token that caused the error, if there is one
+    pub fn token(&self) -> Option<&str> {
+        match self {
+            ParseError::UnrecognizedArgument { token, .. }
+            | ParseError::UnexpectedPositional { token, .. }
+            | ParseError::AmbiguousArgument { token, .. }
+            | ParseError::MissingValue { token, .. }
+            | ParseError::UnexpectedArgument { token, .. }
+            | ParseError::EmptyVector { token, .. }
+            | ParseError::FlagWithValue { token, .. }
+            | ParseError::RepeatedArgument { token, .. }
+            | ParseError::NonFlagInCluster { token, .. } => Some(token),
+            ParseError::MissingRequired { .. }
+            | ParseError::InvalidEnvironmentValue { .. }
+            | ParseError::InvalidChoice { .. }
+            | ParseError::InvalidValue { .. }
+            | ParseError::Conflict { .. }
+            | ParseError::MissingDependency { .. }
+            | ParseError::MissingRequiredIf { .. }
+            | ParseError::MissingRequiredUnless { .. }
+            | ParseError::GroupConflict { .. }
+            | ParseError::GroupMissing { .. }
+            | ParseError::ConfigRead { .. }
+            | ParseError::Config { .. }
+            | ParseError::ResponseFileRead { .. }
+            | ParseError::ResponseFile { .. } => None,
+            ParseError::Subcommand { error, .. } => error.token(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnrecognizedArgument { token, suggestions } => {
+                write!(f, "unrecognized argument found: {}", token)?;
+                write_suggestions(f, suggestions)
+            }
+            ParseError::AmbiguousArgument { token, candidates } => write!(
+                f,
+                "ambiguous argument {}, could be: {}",
+                token,
+                candidates.join(", ")
+            ),
+            ParseError::MissingValue { argument, token } => {
+                write!(f, "expected a value for {} ({})", token, argument)
+            }
+            ParseError::UnexpectedArgument {
+                argument,
+                token,
+                found,
+            } => write!(
+                f,
+                "expected a value for {} ({}) found argument {}",
+                token, argument, found
+            ),
+            ParseError::EmptyVector { argument, token } => {
+                write!(f, "expected value(s) for {} ({})", token, argument)
+            }
+            ParseError::FlagWithValue { argument, token } => {
+                write!(f, "flag {} does not take a value: {}", argument, token)
+            }
+            ParseError::RepeatedArgument { argument, token } => {
+                write!(f, "{} cannot be given more than once: {}", argument, token)
+            }
+            ParseError::NonFlagInCluster { argument, token } => write!(
+                f,
+                "found non flag argument {} clubbed in: {}",
+                argument, token
+            ),
+            ParseError::UnexpectedPositional { token, suggestions } => {
+                write!(f, "unexpected positional argument: {}", token)?;
+                write_suggestions(f, suggestions)
+            }
+            ParseError::InvalidEnvironmentValue {
+                argument,
+                variable,
+                value,
+            } => write!(
+                f,
+                "invalid value {:?} for {} in environment variable {}",
+                value, argument, variable
+            ),
+            ParseError::InvalidChoice {
+                argument,
+                value,
+                choices,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "invalid value {:?} for {}, possible values are: {}",
+                    value,
+                    argument,
+                    choices.join(", ")
+                )?;
+                write_suggestions(f, suggestions)
+            }
+            ParseError::InvalidValue {
+                argument,
+                value,
+                message,
+            } => write!(f, "invalid value {:?} for {}: {}", value, argument, message),
+            ParseError::Conflict { argument, other } => {
+                write!(f, "{} cannot be used with {}", argument, other)
+            }
+            ParseError::MissingDependency { argument, required } => {
+                write!(f, "{} requires {}", argument, required)
+            }
+            ParseError::MissingRequiredIf {
+                argument,
+                other,
+                value,
+            } => write!(f, "{} is required when {} is {}", argument, other, value),
+            ParseError::MissingRequiredUnless { argument, others } => write!(
+                f,
+                "{} is required unless one of {} is given",
+                argument,
+                others.join(", ")
+            ),
+            ParseError::GroupConflict { group, arguments } => write!(
+                f,
+                "only one argument of {} can be given, found: {}",
+                group,
+                arguments.join(", ")
+            ),
+            ParseError::GroupMissing { group, arguments } => write!(
+                f,
+                "one argument of {} is required: {}",
+                group,
+                arguments.join(", ")
+            ),
+            ParseError::ConfigRead { path, message } => {
+                write!(f, "cannot read {}: {}", path.display(), message)
+            }
+            ParseError::Config {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ParseError::ResponseFileRead { path, message } => {
+                write!(
+                    f,
+                    "cannot read response file {}: {}",
+                    path.display(),
+                    message
+                )
+            }
+            ParseError::ResponseFile {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ParseError::MissingRequired { missing } => {
+                let missing = missing
+                    .iter()
+                    .map(|(name, invocators)| {
+                        if invocators.is_empty() {
+                            format!("<{}>", name)
+                        } else {
+                            format!("{} ({})", name, invocators.join(", "))
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                write!(f, "missing required arguments: {}", missing.join("; "))
+            }
+            ParseError::Subcommand { path, error } => {
+                write!(f, "in subcommand `{}`: {}", path.join(" "), error)
+            }
+        }
+    }
+}
+
+/// ` (did you mean a or b?)`, nothing when there are no suggestions
+fn write_suggestions(f: &mut fmt::Formatter<'_>, suggestions: &[String]) -> fmt::Result {
+    if suggestions.is_empty() {
+        return Ok(());
+    }
+    write!(f, " (did you mean {}?)", suggestions.join(" or "))
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Subcommand { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// every way in which reading a parsed value as a specific type can fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    /// the argument was not parsed and has no default
+    Missing { argument: String },
+    /// the value is not the variant that was asked for, e.g. a vector read as a single value
+    WrongVariant {
+        argument: String,
+        expected: String,
+        found: String,
+    },
+    /// the raw text could not be converted to the requested type
+    Invalid {
+        argument: String,
+        value: String,
+        message: String,
+    },
+}
+
+impl ConversionError {
+    pub fn argument(&self) -> &str {
+        match self {
+            ConversionError::Missing { argument }
+            | ConversionError::WrongVariant { argument, .. }
+            | ConversionError::Invalid { argument, .. } => argument,
+        }
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::Missing { argument } => write!(f, "{} was not given", argument),
+            ConversionError::WrongVariant {
+                argument,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected a {} for {} found a {}",
+                expected, argument, found
+            ),
+            ConversionError::Invalid {
+                argument,
+                value,
+                message,
+            } => write!(f, "invalid value {:?} for {}: {}", value, argument, message),
+        }
+    }
+}
+
+impl Error for ConversionError {}
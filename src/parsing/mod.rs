@@ -1,3 +1,8 @@
+pub mod config;
+pub mod errors;
 pub mod parsed_arguments;
 pub mod parser;
+pub(crate) mod response;
+pub mod sources;
+pub(crate) mod suggestions;
 pub mod values;
@@ -1,10 +1,22 @@
+use crate::ConversionError;
 use crate::Value;
+use crate::ValueSource;
 use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Default)]
 pub struct ParsedArguments {
     pub arguments: HashMap<String, Value>,
     count: usize,
+    subcommand: Option<(String, Box<ParsedArguments>)>,
+    trailing: Vec<String>,
+    occurrences: HashMap<String, usize>,
+    groups: HashMap<String, Vec<String>>,
+    sources: HashMap<String, ValueSource>,
+    raw: HashMap<String, Vec<OsString>>,
 }
 
 impl ParsedArguments {
@@ -15,6 +27,12 @@ impl ParsedArguments {
         ParsedArguments {
             arguments: HashMap::with_capacity(capacity),
             count: 0usize,
+            subcommand: None,
+            trailing: Vec::new(),
+            occurrences: HashMap::new(),
+            groups: HashMap::new(),
+            sources: HashMap::new(),
+            raw: HashMap::new(),
         }
     }
     pub fn count(&self) -> usize {
@@ -26,4 +44,207 @@ impl ParsedArguments {
     pub fn contains(&self, name: &str) -> bool {
         self.arguments.contains_key(name)
     }
+    /// convert a flag, count or word value to any type that implements `FromStr`
+    pub fn get<T>(&self, name: &str) -> Result<T, ConversionError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let text = match self.arguments.get(name) {
+            Some(Value::Flag(flag)) => flag.to_string(),
+            Some(Value::Word(word)) => word.clone(),
+            Some(Value::Count(count)) => count.to_string(),
+            Some(value) => {
+                return Err(ConversionError::WrongVariant {
+                    argument: name.to_string(),
+                    expected: "flag, count or word".to_string(),
+                    found: value.kind().to_string(),
+                })
+            }
+            None => {
+                return Err(ConversionError::Missing {
+                    argument: name.to_string(),
+                })
+            }
+        };
+        ParsedArguments::convert(name, &text)
+    }
+    /// convert every element of a vector value to any type that implements `FromStr`
+    pub fn get_many<T>(&self, name: &str) -> Result<Vec<T>, ConversionError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.arguments.get(name) {
+            Some(Value::Vector(vector)) => vector
+                .iter()
+                .map(|f| ParsedArguments::convert(name, f))
+                .collect(),
+            Some(value) => Err(ConversionError::WrongVariant {
+                argument: name.to_string(),
+                expected: "vector".to_string(),
+                found: value.kind().to_string(),
+            }),
+            None => Err(ConversionError::Missing {
+                argument: name.to_string(),
+            }),
+        }
+    }
+    fn convert<T>(name: &str, text: &str) -> Result<T, ConversionError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        text.parse::<T>().map_err(|f| ConversionError::Invalid {
+            argument: name.to_string(),
+            value: text.to_string(),
+            message: f.to_string(),
+        })
+    }
+    pub(crate) fn set_subcommand(&mut self, name: String, parsed: ParsedArguments) {
+        self.subcommand = Some((name, Box::new(parsed)));
+    }
+    /// count one more occurrence of the argument and return how many there are now
+    pub(crate) fn record_occurrence(&mut self, name: &str) -> usize {
+        let occurrences = self.occurrences.entry(name.to_string()).or_insert(0);
+        *occurrences += 1;
+        *occurrences
+    }
+    /// how many times the argument was given on the command line
+    pub fn occurrences(&self, name: &str) -> usize {
+        self.occurrences.get(name).copied().unwrap_or(0)
+    }
+    pub(crate) fn set_group(&mut self, group: &str, given: Vec<String>) {
+        self.groups.insert(group.to_string(), given);
+    }
+    /// the argument that was given from the group, the first one if there are several
+    pub fn chosen(&self, group: &str) -> Option<&str> {
+        self.groups
+            .get(group)
+            .and_then(|f| f.first())
+            .map(String::as_str)
+    }
+    /// every argument that was given from the group
+    pub fn chosen_all(&self, group: &str) -> &[String] {
+        self.groups.get(group).map_or(&[], Vec::as_slice)
+    }
+    /// store a value together with where it came from
+    pub(crate) fn insert(&mut self, name: String, value: Value, source: ValueSource) {
+        self.sources.insert(name.clone(), source);
+        self.arguments.insert(name, value);
+    }
+    /// move the command line indices of every value, including the ones of subcommands, up by `by`
+    pub(crate) fn shift_command_line(&mut self, by: usize) {
+        for source in self.sources.values_mut() {
+            if let ValueSource::CommandLine { index } = source {
+                *index += by;
+            }
+        }
+        if let Some((_, subcommand)) = &mut self.subcommand {
+            subcommand.shift_command_line(by);
+        }
+    }
+    /// where the value of the argument came from, `None` if it has no value
+    pub fn source(&self, name: &str) -> Option<&ValueSource> {
+        self.sources.get(name)
+    }
+    /// keep the values of an argument as they were given, appending to the earlier ones or replacing them
+    pub(crate) fn set_raw(&mut self, name: &str, values: Vec<OsString>, append: bool) {
+        let raw = self.raw.entry(name.to_string()).or_default();
+        if !append {
+            raw.clear();
+        }
+        raw.extend(values);
+    }
+    /// the value of a flag, count or word argument as it was given  
+    /// values not given as `OsString`s are converted from their text
+    pub fn get_os(&self, name: &str) -> Option<OsString> {
+        match (self.raw.get(name), self.arguments.get(name)?) {
+            (_, Value::Vector(_)) => None,
+            (Some(raw), _) => raw.last().cloned(),
+            (None, value) => Some(OsString::from(value.to_string())),
+        }
+    }
+    /// every value of an argument as it was given, a single value for non vector arguments
+    pub fn get_many_os(&self, name: &str) -> Option<Vec<OsString>> {
+        match self.raw.get(name) {
+            Some(raw) => Some(raw.clone()),
+            None => {
+                let value = self.arguments.get(name)?.clone();
+                Some(value.into_vec().into_iter().map(OsString::from).collect())
+            }
+        }
+    }
+    /// the value of a word argument as a path, without losing characters that are not unicode
+    pub fn get_path(&self, name: &str) -> Option<PathBuf> {
+        self.get_os(name).map(PathBuf::from)
+    }
+    pub(crate) fn set_trailing(&mut self, trailing: Vec<String>) {
+        self.trailing = trailing;
+    }
+    /// arguments after the `--` terminator that were not taken by positional arguments
+    pub fn trailing(&self) -> &[String] {
+        &self.trailing
+    }
+    /// the selected subcommand and its parsed arguments
+    pub fn subcommand(&self) -> Option<(&str, &ParsedArguments)> {
+        self.subcommand
+            .as_ref()
+            .map(|(name, parsed)| (name.as_str(), parsed.as_ref()))
+    }
+    pub fn subcommand_name(&self) -> Option<&str> {
+        self.subcommand().map(|f| f.0)
+    }
+    /// names of the selected subcommands from the outermost to the innermost
+    pub fn subcommand_path(&self) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut current = self;
+        while let Some((name, parsed)) = current.subcommand() {
+            path.push(name);
+            current = parsed;
+        }
+        path
+    }
+}
+
+mod parsed_arguments_tests {
+    #[test]
+    fn typed_values() {
+        let mut parsed = super::ParsedArguments::new();
+        parsed
+            .arguments
+            .insert("port".to_string(), super::Value::from("8080"));
+        parsed
+            .arguments
+            .insert("sizes".to_string(), super::Value::from(vec!["1", "2", "x"]));
+        parsed
+            .arguments
+            .insert("verbose".to_string(), super::Value::from(true));
+        assert_eq!(parsed.get::<u16>("port"), Ok(8080));
+        assert_eq!(parsed.get::<bool>("verbose"), Ok(true));
+        assert_eq!(
+            parsed.get::<u8>("missing"),
+            Err(super::ConversionError::Missing {
+                argument: "missing".to_string()
+            })
+        );
+        assert_eq!(
+            parsed.get::<u8>("sizes"),
+            Err(super::ConversionError::WrongVariant {
+                argument: "sizes".to_string(),
+                expected: "flag, count or word".to_string(),
+                found: "vector".to_string()
+            })
+        );
+        let error = parsed.get_many::<u8>("sizes").unwrap_err();
+        assert!(matches!(
+            &error,
+            super::ConversionError::Invalid { argument, value, .. } if argument == "sizes" && value == "x"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "invalid value \"x\" for sizes: invalid digit found in string"
+        );
+        assert!(parsed.get_many::<u8>("port").is_err());
+    }
 }
@@ -1,14 +1,46 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::iter::Peekable;
+use std::path::Path;
 
+use crate::parsing::config::ConfigFile;
+use crate::parsing::response;
+use crate::parsing::suggestions;
+use crate::ArgGroup;
 use crate::Argument;
+use crate::Arity;
+use crate::ParseError;
 use crate::ParsedArguments;
+use crate::RepeatPolicy;
 use crate::Value;
+use crate::ValueSource;
+
+/// where arguments missing from the command line are looked up, in order of precedence
+struct Layers<'a> {
+    env: &'a HashMap<String, String>,
+    config: Option<&'a ConfigFile>,
+    /// the arguments as given when they were not strings, indexed like the command line
+    raw: Option<&'a [OsString]>,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Parser {
     pub arguments: HashMap<String, Argument>,
-    invocators: HashSet<String>,
+    pub(crate) invocators: HashSet<String>,
+    pub(crate) order: Vec<String>,
+    pub(crate) positionals: Vec<String>,
+    pub(crate) subcommands: HashMap<String, Parser>,
+    pub(crate) subcommand_order: Vec<String>,
+    pub(crate) groups: Vec<ArgGroup>,
+    pub(crate) about: String,
+    pub(crate) program: String,
+    pub(crate) help_width: usize,
+    pub(crate) env_prefix: String,
+    pub(crate) env_separator: Option<char>,
+    pub(crate) abbreviations: bool,
+    pub(crate) response_files: bool,
     count: usize,
 }
 
@@ -20,36 +52,253 @@ impl Parser {
         Parser {
             arguments: HashMap::with_capacity(capacity),
             invocators: HashSet::with_capacity(capacity * 4usize),
-            count: 0,
+            order: Vec::with_capacity(capacity),
+            positionals: Vec::new(),
+            subcommands: HashMap::new(),
+            ..Parser::default()
         }
     }
+    /// description of the program shown in the help text
+    pub fn about(mut self, about: &str) -> Parser {
+        self.about = about.to_string();
+        self
+    }
+    /// name of the program shown in the usage line
+    pub fn program_name(mut self, program: &str) -> Parser {
+        self.program = program.to_string();
+        self
+    }
+    /// width the help text is wrapped to, 80 columns by default
+    pub fn help_width(mut self, width: usize) -> Parser {
+        self.help_width = width;
+        self
+    }
+    /// read arguments that are not given from `PREFIX_NAME` environment variables  
+    /// the name is upper cased with dashes turned into underscores, `--dry-run` becomes `PREFIX_DRY_RUN`  
+    /// arguments with their own `env` variable ignore the prefix
+    pub fn env_prefix(mut self, prefix: &str) -> Parser {
+        self.env_prefix = prefix.to_string();
+        self
+    }
+    /// separator between the values of a vector argument read from the environment, `,` by default
+    pub fn env_separator(mut self, separator: char) -> Parser {
+        self.env_separator = Some(separator);
+        self
+    }
+    /// accept unique prefixes of long invocators, `--drag` for `--dragon-colors`  
+    /// negated forms take part as well, `--no-sl` for `--no-sleep`  
+    /// applies to this parser only, subcommands have their own setting
+    pub fn allow_abbreviations(mut self, allow: bool) -> Parser {
+        self.abbreviations = allow;
+        self
+    }
+    /// replace `@path` arguments with the arguments read from the file at `path`  
+    /// files are split on whitespace with shell like quoting and `#` comments, and can include
+    /// other files, `@@value` stands for the argument `@value`
+    pub fn response_files(mut self, allow: bool) -> Parser {
+        self.response_files = allow;
+        self
+    }
+    /// add an argument, panicking if it does not fit the parser, see `check_argument`
     pub fn add_argument(&mut self, argument: Argument) {
-        assert!(&argument.is_valid());
-        let is_flag = argument.get_type().eq("flag");
+        if let Err(message) = self.check_argument(&argument) {
+            panic!("{}", message);
+        }
+        if argument.is_positional() {
+            self.add_positional(argument);
+            return;
+        }
+        let is_flag = !argument.takes_value();
         for item in &argument.invoke_with {
-            assert!(
-                !self.invocators.contains(item),
-                "duplicate invocator found: {}",
-                &item
-            );
             self.invocators.insert(item.to_string());
             if is_flag {
-                if item.starts_with("--") {
-                    self.invocators
-                        .insert(format!("--no-{}", &item.strip_prefix("--").unwrap()));
-                } else if item.starts_with('-') {
-                    self.invocators
-                        .insert(format!("-no-{}", &item.strip_prefix('-').unwrap()));
-                } else {
-                    self.invocators.insert(format!("no-{}", &item));
-                }
+                self.invocators.insert(Parser::negate(item));
+            };
+        }
+        self.order.push(argument.name.clone());
+        self.arguments.insert(argument.name.clone(), argument);
+        self.count += 1;
+    }
+    /// the reason the argument cannot be added to the parser, if there is one  
+    /// the argument needs a name, a default value that matches its type, its possible values
+    /// and its validator, and no default if required,
+    /// every argument it refers to must already be added, and its invocators or positional index
+    /// must not be taken
+    pub(crate) fn check_argument(&self, argument: &Argument) -> Result<(), String> {
+        if !argument.is_valid() {
+            return Err("argument has no name".to_string());
+        }
+        if self.arguments.contains_key(&argument.name) {
+            return Err(format!("duplicate argument name found: {}", &argument.name));
+        }
+        if let Some(default) = argument.get_default_value() {
+            if !argument.accepts_value(default) {
+                return Err(format!(
+                    "default value of {} does not match its type: {}",
+                    &argument.name,
+                    argument.get_type()
+                ));
+            }
+            if argument.is_required() {
+                return Err(format!(
+                    "required argument cannot have a default value: {}",
+                    &argument.name
+                ));
+            }
+            let values = match default {
+                Value::Word(word) => std::slice::from_ref(word),
+                Value::Vector(vector) => vector.as_slice(),
+                _ => &[],
             };
+            let choices = argument.get_possible_values();
+            for value in values {
+                if !choices.is_empty() && !choices.contains(value) {
+                    return Err(format!(
+                        "default value of {} is not one of its possible values: {}",
+                        &argument.name, value
+                    ));
+                }
+                if let Some(Err(message)) = argument.get_validator().map(|f| f.validate(value)) {
+                    return Err(format!(
+                        "default value of {} is invalid: {}",
+                        &argument.name, message
+                    ));
+                }
+            }
+        }
+        if let Some(related) = argument
+            .related()
+            .into_iter()
+            .find(|f| !self.arguments.contains_key(*f))
+        {
+            return Err(format!(
+                "unknown argument referenced by {}: {}",
+                &argument.name, related
+            ));
+        }
+        if argument.is_positional() {
+            return self.check_positional(argument);
+        }
+        for item in &argument.invoke_with {
+            if self.invocators.contains(item) {
+                return Err(format!("duplicate invocator found: {}", &item));
+            }
+            if self.subcommands.contains_key(item) {
+                return Err(format!("invocator clashes with a subcommand: {}", &item));
+            }
+        }
+        Ok(())
+    }
+    fn check_positional(&self, argument: &Argument) -> Result<(), String> {
+        if !argument.invoke_with.is_empty() {
+            return Err(format!(
+                "positional argument cannot have invocators: {}",
+                &argument.name
+            ));
+        }
+        let index = argument.get_index().unwrap_or(self.positionals.len());
+        if self
+            .positionals
+            .iter()
+            .any(|f| self.arguments[f].get_index() == Some(index))
+        {
+            return Err(format!("duplicate positional index found: {}", index));
+        }
+        Ok(())
+    }
+    /// add a subcommand with its own set of arguments  
+    /// the subcommand is selected by its name and the rest of the arguments are parsed by its parser  
+    /// subcommands can have subcommands of their own
+    pub fn add_subcommand(&mut self, name: &str, mut parser: Parser) {
+        if let Err(message) = self.check_subcommand(name) {
+            panic!("{}", message);
+        }
+        if parser.program.is_empty() {
+            parser.program = name.to_string();
+        }
+        self.subcommand_order.push(name.to_string());
+        self.subcommands.insert(name.to_string(), parser);
+    }
+    /// the reason a subcommand cannot be added under the name, if there is one
+    pub(crate) fn check_subcommand(&self, name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("subcommand name cannot be empty".to_string());
+        }
+        if self.subcommands.contains_key(name) || self.invocators.contains(name) {
+            return Err(format!("duplicate subcommand found: {}", name));
         }
+        Ok(())
+    }
+    /// add a group of arguments that is checked as a whole, its arguments must already be added
+    pub fn add_group(&mut self, group: ArgGroup) {
+        if let Err(message) = self.check_group(&group) {
+            panic!("{}", message);
+        }
+        self.groups.push(group);
+    }
+    /// the reason the group cannot be added, if there is one
+    pub(crate) fn check_group(&self, group: &ArgGroup) -> Result<(), String> {
+        if group.name.is_empty() {
+            return Err("group name cannot be empty".to_string());
+        }
+        if self.groups.iter().any(|f| f.name == group.name) {
+            return Err(format!("duplicate group found: {}", &group.name));
+        }
+        if let Some(name) = group
+            .get_arguments()
+            .iter()
+            .find(|f| !self.arguments.contains_key(*f))
+        {
+            return Err(format!(
+                "unknown argument in group {}: {}",
+                &group.name, name
+            ));
+        }
+        Ok(())
+    }
+    pub fn get_group(&self, name: &str) -> Option<&ArgGroup> {
+        self.groups.iter().find(|f| f.name == name)
+    }
+    pub fn get_subcommand(&self, name: &str) -> Option<&Parser> {
+        self.subcommands.get(name)
+    }
+    /// positionals are kept sorted by their index instead of being given invocators
+    fn add_positional(&mut self, argument: Argument) {
+        let index = argument.get_index().unwrap_or(self.positionals.len());
+        let argument = argument.index(index);
+        self.positionals.push(argument.name.clone());
+        self.order.push(argument.name.clone());
         self.arguments.insert(argument.name.clone(), argument);
+        let arguments = &self.arguments;
+        self.positionals
+            .sort_by_key(|f| arguments[f].get_index().unwrap());
         self.count += 1;
     }
-    fn detect_negation(s: &str) -> bool {
-        s.starts_with("--no-") || s.starts_with("-no-") || s.starts_with("no-")
+    /// the negated form of a flag invocator, e.g. `--no-sleep` for `--sleep`
+    pub(crate) fn negate(item: &str) -> String {
+        if let Some(long) = item.strip_prefix("--") {
+            format!("--no-{}", long)
+        } else if let Some(short) = item.strip_prefix('-') {
+            format!("-no-{}", short)
+        } else {
+            format!("no-{}", item)
+        }
+    }
+    /// the invocators of an argument, followed by their negated forms when it takes no value
+    pub(crate) fn all_invocators(argument: &Argument) -> Vec<String> {
+        let mut invocators = argument
+            .invocators()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if !argument.takes_value() {
+            let negated = invocators
+                .iter()
+                .map(|f| Parser::negate(f))
+                .collect::<Vec<_>>();
+            invocators.extend(negated);
+        }
+        invocators
     }
     fn negation_type(s: &str) -> u8 {
         if s.starts_with("--no-") {
@@ -76,6 +325,9 @@ impl Parser {
         self.invocators.contains(&other)
     }
     pub fn get_argument(&self, other: &str) -> Option<&Argument> {
+        if let Some(argument) = self.arguments.values().find(|f| f.same_as(other)) {
+            return Some(argument);
+        }
         let other = Parser::strip_negation(other);
         self.arguments.values().find(|f| f.same_as(&other))
     }
@@ -97,116 +349,850 @@ impl Parser {
             Some(vector)
         }
     }
+    /// a dash followed by anything that is not a number, e.g. `-q` but not `-5` or `-`
+    fn looks_like_option(word: &str) -> bool {
+        word.starts_with('-') && word.len() > 1 && word.parse::<f64>().is_err()
+    }
+    /// true if the word is an invocator, possibly with an attached value,
+    /// a cluster made up entirely of invocators, or the `--` terminator
+    fn is_argument(&self, word: &str) -> bool {
+        if word == "--" || self.contains_argument(word) || self.split_invocation(word).is_some() {
+            return true;
+        }
+        if !self.abbreviations_of(word).is_empty() {
+            return true;
+        }
+        match Parser::break_apart(word) {
+            Some(broken) => !broken.is_empty() && broken.iter().all(|f| self.contains_argument(f)),
+            None => false,
+        }
+    }
+    /// the variables of the process environment, skipping the ones that are not valid unicode
+    fn process_env() -> HashMap<String, String> {
+        std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect()
+    }
+    /// parse the arguments, panicking with the error message on failure  
+    /// see `try_parse_arguments` for a fallible version
     pub fn parse_arguments<T: ToString>(&self, arguments: &[T]) -> ParsedArguments {
-        let mut parsed = ParsedArguments::new();
-        let mut env_arguments = arguments.iter().map(T::to_string).peekable();
-        while env_arguments.peek().is_some() {
-            let word = env_arguments.next().unwrap();
-            if self.invocators.contains(&word) {
-                let argument = self.get_argument(&word).unwrap();
-                let name = argument.name.clone();
-                let negation = Parser::detect_negation(&word);
-                match argument.get_type() {
-                    "flag" => {
-                        parsed.arguments.insert(name, Value::from(!negation));
-                        continue;
+        match self.try_parse_arguments(arguments) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// parse the arguments, returning a `ParseError` instead of panicking  
+    /// missing arguments are read from the environment of the process
+    pub fn try_parse_arguments<T: ToString>(
+        &self,
+        arguments: &[T],
+    ) -> Result<ParsedArguments, ParseError> {
+        let env = Parser::process_env();
+        self.try_parse_arguments_with_env(arguments, &env)
+    }
+    /// parse the arguments the process was started with, see `try_parse_env`  
+    /// on failure the error and the usage line are printed to standard error
+    /// and the process exits with status 2
+    pub fn parse_env(&mut self) -> ParsedArguments {
+        match self.try_parse_env() {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                eprintln!("{}", self.render_usage());
+                std::process::exit(2);
+            }
+        }
+    }
+    /// parse the arguments the process was started with, read with `std::env::args_os`  
+    /// the first one is the program, its file name becomes the program name shown in the usage
+    /// line unless one was set with `program_name`
+    pub fn try_parse_env(&mut self) -> Result<ParsedArguments, ParseError> {
+        let argv = std::env::args_os().collect::<Vec<_>>();
+        self.try_parse_argv(&argv)
+    }
+    /// parse a full command line, program included, command line indices count the program
+    fn try_parse_argv<T: AsRef<OsStr>>(
+        &mut self,
+        argv: &[T],
+    ) -> Result<ParsedArguments, ParseError> {
+        let (program, arguments) = match argv.split_first() {
+            Some((program, arguments)) => (Some(program.as_ref()), arguments),
+            None => (None, argv),
+        };
+        if let Some(program) = program.filter(|_| self.program.is_empty()) {
+            let name = Path::new(program).file_name().unwrap_or(program);
+            self.program = name.to_string_lossy().into_owned();
+        }
+        let mut parsed = self.try_parse_arguments_os(arguments)?;
+        parsed.shift_command_line(argv.len() - arguments.len());
+        Ok(parsed)
+    }
+    /// parse arguments that may not be valid unicode, such as the ones from `std::env::args_os`  
+    /// invocators are matched on the lossy conversion of each token, the values as given
+    /// can be read with `ParsedArguments::get_os` and `get_path`
+    pub fn parse_arguments_os<T: AsRef<OsStr>>(&self, arguments: &[T]) -> ParsedArguments {
+        match self.try_parse_arguments_os(arguments) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// fallible version of `parse_arguments_os`
+    pub fn try_parse_arguments_os<T: AsRef<OsStr>>(
+        &self,
+        arguments: &[T],
+    ) -> Result<ParsedArguments, ParseError> {
+        let env = Parser::process_env();
+        self.try_parse_arguments_os_with_env(arguments, &env)
+    }
+    /// `parse_arguments_os` with the given variables standing in for the environment
+    pub fn parse_arguments_os_with_env<T: AsRef<OsStr>>(
+        &self,
+        arguments: &[T],
+        env: &HashMap<String, String>,
+    ) -> ParsedArguments {
+        match self.try_parse_arguments_os_with_env(arguments, env) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// fallible version of `parse_arguments_os_with_env`
+    pub fn try_parse_arguments_os_with_env<T: AsRef<OsStr>>(
+        &self,
+        arguments: &[T],
+        env: &HashMap<String, String>,
+    ) -> Result<ParsedArguments, ParseError> {
+        let arguments = arguments.iter().map(|f| f.as_ref().to_os_string());
+        self.parse_command_line(arguments.collect(), env, None, true)
+    }
+    /// parse the arguments with the given variables standing in for the environment
+    pub fn parse_arguments_with_env<T: ToString>(
+        &self,
+        arguments: &[T],
+        env: &HashMap<String, String>,
+    ) -> ParsedArguments {
+        match self.try_parse_arguments_with_env(arguments, env) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// fallible version of `parse_arguments_with_env`
+    pub fn try_parse_arguments_with_env<T: ToString>(
+        &self,
+        arguments: &[T],
+        env: &HashMap<String, String>,
+    ) -> Result<ParsedArguments, ParseError> {
+        let arguments = arguments.iter().map(|f| OsString::from(f.to_string()));
+        self.parse_command_line(arguments.collect(), env, None, false)
+    }
+    /// parse the arguments with a configuration file below the command line and the environment  
+    /// precedence is command line, then environment, then configuration file, then defaults  
+    /// see `ConfigFile` for the format of the file
+    pub fn parse_with_config<T: ToString, P: AsRef<Path>>(
+        &self,
+        arguments: &[T],
+        path: P,
+    ) -> ParsedArguments {
+        match self.try_parse_with_config(arguments, path) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// fallible version of `parse_with_config`
+    pub fn try_parse_with_config<T: ToString, P: AsRef<Path>>(
+        &self,
+        arguments: &[T],
+        path: P,
+    ) -> Result<ParsedArguments, ParseError> {
+        let config = ConfigFile::read(path.as_ref())?;
+        let env = Parser::process_env();
+        self.try_parse_with_config_file(arguments, &env, &config)
+    }
+    /// parse the arguments with an already loaded configuration file and the given environment
+    pub fn parse_with_config_file<T: ToString>(
+        &self,
+        arguments: &[T],
+        env: &HashMap<String, String>,
+        config: &ConfigFile,
+    ) -> ParsedArguments {
+        match self.try_parse_with_config_file(arguments, env, config) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// fallible version of `parse_with_config_file`
+    pub fn try_parse_with_config_file<T: ToString>(
+        &self,
+        arguments: &[T],
+        env: &HashMap<String, String>,
+        config: &ConfigFile,
+    ) -> Result<ParsedArguments, ParseError> {
+        self.check_config(config)?;
+        let arguments = arguments.iter().map(|f| OsString::from(f.to_string()));
+        self.parse_command_line(arguments.collect(), env, Some(config), false)
+    }
+    /// expand the response files if they are allowed and parse the arguments  
+    /// `keep_raw` keeps the values as given, for arguments that may not be valid unicode
+    fn parse_command_line(
+        &self,
+        arguments: Vec<OsString>,
+        env: &HashMap<String, String>,
+        config: Option<&ConfigFile>,
+        keep_raw: bool,
+    ) -> Result<ParsedArguments, ParseError> {
+        let raw = if self.response_files {
+            response::expand(arguments)?
+        } else {
+            arguments
+        };
+        let lossy = raw
+            .iter()
+            .map(|f| f.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let layers = Layers {
+            env,
+            config,
+            raw: Some(raw.as_slice()).filter(|_| keep_raw),
+        };
+        self.parse_layered(&lossy, &layers, &[], 0)
+    }
+    /// reject unknown sections and keys, and values that do not fit their argument,
+    /// including the ones in sections of subcommands that are not used
+    fn check_config(&self, config: &ConfigFile) -> Result<(), ParseError> {
+        let error = |line: usize, message: String| ParseError::Config {
+            path: config.path.clone(),
+            line,
+            message,
+        };
+        let mut sections = vec![(String::new(), 0usize)];
+        sections.extend(config.sections().iter().cloned());
+        for (section, line) in sections {
+            let mut parser = self;
+            if !section.is_empty() {
+                for name in section.split('.') {
+                    parser = match parser.subcommands.get(name) {
+                        Some(subcommand) => subcommand,
+                        None => return Err(error(line, format!("unknown section: {}", section))),
+                    };
+                }
+            }
+            for entry in config.entries(&section) {
+                let argument = match parser.arguments.get(&entry.key) {
+                    Some(argument) => argument,
+                    None => return Err(error(entry.line, format!("unknown key: {}", entry.key))),
+                };
+                let value = match parser.value_from_text(argument, &entry.value) {
+                    Some(value) => value,
+                    None => {
+                        return Err(error(
+                            entry.line,
+                            format!("invalid value {:?} for {}", entry.value, entry.key),
+                        ))
                     }
-                    "word" => {
-                        let next = match env_arguments.next() {
-                            Some(some_string) => some_string,
-                            None => panic!("expected a value for {}", &word),
-                        };
-                        assert!(
-                            !self.contains_argument(&next),
-                            "expected a value for {} found argument {}",
-                            &word,
-                            &next,
-                        );
-                        let broken = match Parser::break_apart(&next) {
-                            Some(broken_value) => broken_value,
-                            None => {
-                                parsed.arguments.insert(name, Value::from(next));
-                                continue;
-                            }
-                        };
-                        assert!(
-                            !broken.iter().all(|f| { self.contains_argument(&f) }),
-                            "expected a value for {} found argument {}",
-                            &word,
-                            &next,
-                        );
-                        parsed.arguments.insert(name, Value::from(next));
-                        continue;
+                };
+                let values = match &value {
+                    Value::Word(word) => std::slice::from_ref(word),
+                    Value::Vector(vector) => vector.as_slice(),
+                    _ => &[],
+                };
+                let choices = argument.get_possible_values();
+                for value in values {
+                    if !choices.is_empty() && !choices.contains(value) {
+                        return Err(error(
+                            entry.line,
+                            format!(
+                                "invalid value {:?} for {}, possible values are: {}",
+                                value,
+                                entry.key,
+                                choices.join(", ")
+                            ),
+                        ));
                     }
-                    "vector" => {
-                        let mut value = Vec::<String>::new();
-                        assert!(
-                            env_arguments.peek().is_some(),
-                            "expected value(s) for {}",
-                            &word
-                        );
-                        while env_arguments.peek().is_some() {
-                            let next = env_arguments.peek().unwrap();
-                            let broken = Parser::break_apart(&next);
-                            if self.contains_argument(&next) {
-                                assert!(!value.is_empty(), "expected value(s) for {}", &word);
-                                parsed.arguments.insert(name, Value::from(value));
-                                break;
-                            } else if broken.is_none() {
-                                value.push(env_arguments.next().unwrap());
-                                continue;
-                            } else {
-                                let broken = broken.unwrap();
-                                let is_argument = broken.iter().all(|f| self.contains_argument(f));
-                                if value.is_empty() {
-                                    assert!(
-                                        !is_argument,
-                                        "expected value(s) for {} found argument {}",
-                                        &word, &next
-                                    );
-                                }
-                                if is_argument {
-                                    parsed.arguments.insert(name, Value::from(value));
-                                    break;
-                                } else {
-                                    value.push(env_arguments.next().unwrap());
-                                    if env_arguments.peek().is_none() {
-                                        parsed.arguments.insert(name, Value::from(value));
-                                        break;
-                                    }
-                                    continue;
-                                }
-                            }
-                        }
+                    if let Some(Err(message)) = argument.get_validator().map(|f| f.validate(value))
+                    {
+                        return Err(error(
+                            entry.line,
+                            format!("invalid value {:?} for {}: {}", value, entry.key, message),
+                        ));
                     }
-                    _ => unreachable!(),
-                };
+                }
+            }
+        }
+        Ok(())
+    }
+    /// parse the arguments of the parser found at `path` among the subcommands  
+    /// `offset` is the index of the first argument among all the arguments given
+    fn parse_layered<T: ToString>(
+        &self,
+        arguments: &[T],
+        layers: &Layers,
+        path: &[String],
+        offset: usize,
+    ) -> Result<ParsedArguments, ParseError> {
+        let mut parsed = ParsedArguments::new();
+        let mut positional_values = Vec::<(usize, String)>::new();
+        let mut terminated = None;
+        let mut env_arguments = arguments.iter().map(T::to_string).peekable();
+        while let Some(word) = env_arguments.next() {
+            let index = offset + arguments.len() - env_arguments.len() - 1;
+            if word == "--" {
+                terminated = Some(positional_values.len());
+                let rest = env_arguments.by_ref().enumerate();
+                positional_values.extend(rest.map(|(i, f)| (index + 1 + i, f)));
+            } else if let Some((invocator, attached)) = self.split_invocation(&word) {
+                let before = env_arguments.len();
+                let prefix = word.len() - attached.as_ref().map_or(word.len(), String::len);
+                self.parse_invocation(
+                    &word,
+                    &invocator,
+                    attached,
+                    &mut env_arguments,
+                    &mut parsed,
+                    index,
+                )?;
+                if let Some(raw) = layers.raw {
+                    let consumed = before - env_arguments.len();
+                    self.record_raw(
+                        &mut parsed,
+                        &invocator,
+                        &raw[index..=index + consumed],
+                        &word[..prefix],
+                    );
+                }
+            } else if let Some(subcommand) = self.subcommands.get(&word) {
+                let rest = env_arguments.by_ref().collect::<Vec<_>>();
+                let mut sub_path = path.to_vec();
+                sub_path.push(word.clone());
+                let sub_parsed = subcommand
+                    .parse_layered(&rest, layers, &sub_path, index + 1)
+                    .map_err(|f| f.in_subcommand(&word))?;
+                parsed.set_subcommand(word, sub_parsed);
             } else {
                 let broken = match Parser::break_apart(&word) {
                     Some(value) => value,
-                    None => panic!("unrecognized argument found: {}", &word),
+                    None => return Err(self.unrecognized(word)),
                 };
-                let is_argument = broken.iter().all(|f| self.contains_argument(f));
+                let is_argument =
+                    !broken.is_empty() && broken.iter().all(|f| self.contains_argument(f));
                 if is_argument {
-                    assert!(
-                        broken
-                            .iter()
-                            .all(|f| { self.get_argument(f).unwrap().get_type().eq("flag") }),
-                        "found non flag argument clubbed in: {}",
-                        &word
-                    );
+                    let non_flag = broken
+                        .iter()
+                        .map(|f| self.get_argument(f).unwrap())
+                        .find(|f| f.takes_value());
+                    if let Some(non_flag) = non_flag {
+                        return Err(ParseError::NonFlagInCluster {
+                            argument: non_flag.name.clone(),
+                            token: word,
+                        });
+                    }
                     for item in broken {
-                        let this_arg = self.get_argument(&item).unwrap();
-                        parsed
-                            .arguments
-                            .insert(this_arg.name.clone(), Value::from(true));
+                        self.parse_invocation(
+                            &word,
+                            &item,
+                            None,
+                            &mut env_arguments,
+                            &mut parsed,
+                            index,
+                        )?;
+                    }
+                } else if Parser::looks_like_option(&word) {
+                    return Err(self.unrecognized(word));
+                } else {
+                    positional_values.push((index, word));
+                }
+            }
+        }
+        let terminated = terminated.unwrap_or(positional_values.len());
+        let trailing =
+            self.assign_positionals(positional_values, terminated, layers.raw, &mut parsed)?;
+        parsed.set_trailing(trailing);
+        self.fill_environment(&mut parsed, layers.env)?;
+        if let Some(config) = layers.config {
+            self.fill_config(&mut parsed, config, &path.join("."));
+        }
+        self.validate_values(&parsed)?;
+        self.check_required(&parsed)?;
+        self.check_relations(&parsed)?;
+        self.check_groups(&mut parsed)?;
+        self.fill_defaults(&mut parsed);
+        Ok(parsed)
+    }
+    /// the error for an unknown option, suggesting the closest invocators  
+    /// an abbreviation that fits more than one invocator is reported as ambiguous instead
+    fn unrecognized(&self, token: String) -> ParseError {
+        let candidates = self.abbreviations_of(&token);
+        if candidates.len() > 1 {
+            let candidates = candidates.into_iter().map(str::to_string).collect();
+            return ParseError::AmbiguousArgument { token, candidates };
+        }
+        let invocator = token.split_once('=').map_or(token.as_str(), |f| f.0);
+        let suggestions =
+            suggestions::suggest(invocator, self.invocators.iter().map(String::as_str));
+        ParseError::UnrecognizedArgument { token, suggestions }
+    }
+    /// find the invocator at the start of the word and the value attached to it  
+    /// values are attached with `=` as in `--name=value`, or directly as in `-nvalue`
+    /// for short invocators of word and vector arguments  
+    /// unique abbreviations of long invocators are expanded when they are allowed
+    fn split_invocation(&self, word: &str) -> Option<(String, Option<String>)> {
+        if self.invocators.contains(word) {
+            return Some((word.to_string(), None));
+        }
+        if let Some((invocator, value)) = word.split_once('=') {
+            if self.invocators.contains(invocator) {
+                return Some((invocator.to_string(), Some(value.to_string())));
+            }
+        }
+        if let [invocator] = self.abbreviations_of(word).as_slice() {
+            let value = word.split_once('=').map(|f| f.1.to_string());
+            return Some((invocator.to_string(), value));
+        }
+        if word.starts_with('-') && !word.starts_with("--") {
+            let split = word.char_indices().nth(2).map(|f| f.0)?;
+            let (invocator, value) = word.split_at(split);
+            let takes_value = self
+                .get_argument(invocator)
+                .is_some_and(|f| f.same_as(invocator) && f.takes_value());
+            if takes_value {
+                return Some((invocator.to_string(), Some(value.to_string())));
+            }
+        }
+        None
+    }
+    /// keep the values of a value taking argument as they were given  
+    /// `tokens` start with the invocator, `prefix` is the invocator part of the first token
+    /// when a value is attached to it and empty otherwise
+    fn record_raw(
+        &self,
+        parsed: &mut ParsedArguments,
+        invocator: &str,
+        tokens: &[OsString],
+        prefix: &str,
+    ) {
+        let argument = self.get_argument(invocator).unwrap();
+        if !argument.takes_value() {
+            return;
+        }
+        let mut values = Vec::with_capacity(tokens.len());
+        if !prefix.is_empty() {
+            values.extend(response::strip_prefix(&tokens[0], prefix).map(OsStr::to_os_string));
+        }
+        values.extend(tokens[1..].iter().cloned());
+        let append = argument.get_repeat_policy() == RepeatPolicy::Append;
+        parsed.set_raw(&argument.name, values, append);
+    }
+    /// the long invocators the word is an abbreviation of, sorted  
+    /// empty unless abbreviations are allowed, invocators that mean the same,
+    /// such as two aliases of a flag, are only listed once
+    fn abbreviations_of(&self, word: &str) -> Vec<&str> {
+        let prefix = word.split_once('=').map_or(word, |f| f.0);
+        if !self.abbreviations || !prefix.starts_with("--") || prefix.len() <= 2 {
+            return Vec::new();
+        }
+        let mut candidates = self
+            .invocators
+            .iter()
+            .filter(|f| f.starts_with("--") && f.starts_with(prefix))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+        let mut meanings = Vec::new();
+        candidates.retain(|f| {
+            let argument = self.get_argument(f).unwrap();
+            let meaning = (argument.name.as_str(), argument.same_as(f));
+            if meanings.contains(&meaning) {
+                return false;
+            }
+            meanings.push(meaning);
+            true
+        });
+        candidates
+    }
+    /// handle an invocator and the values that belong to it  
+    /// `word` is the token as given, `attached` the value that was part of the token,
+    /// `index` the position of the token
+    fn parse_invocation<I: Iterator<Item = String>>(
+        &self,
+        word: &str,
+        invocator: &str,
+        attached: Option<String>,
+        env_arguments: &mut Peekable<I>,
+        parsed: &mut ParsedArguments,
+        index: usize,
+    ) -> Result<(), ParseError> {
+        let argument = self.get_argument(invocator).unwrap();
+        let name = argument.name.clone();
+        let negation = !argument.same_as(invocator);
+        let occurrences = parsed.record_occurrence(&name);
+        if occurrences > 1 && argument.get_repeat_policy() == RepeatPolicy::Error {
+            return Err(ParseError::RepeatedArgument {
+                argument: name,
+                token: word.to_string(),
+            });
+        }
+        let value = match argument.get_type() {
+            "flag" => {
+                if attached.is_some() {
+                    return Err(ParseError::FlagWithValue {
+                        argument: name,
+                        token: word.to_string(),
+                    });
+                }
+                Value::from(!negation)
+            }
+            "count" => {
+                if attached.is_some() {
+                    return Err(ParseError::FlagWithValue {
+                        argument: name,
+                        token: word.to_string(),
+                    });
+                }
+                let count = match parsed.get_value(&name) {
+                    _ if negation => 0,
+                    Some(Value::Count(count)) => count + 1,
+                    _ => 1,
+                };
+                Value::from(count)
+            }
+            "word" => {
+                let next = match attached {
+                    Some(value) => value,
+                    None => match env_arguments.next() {
+                        Some(next) if self.is_argument(&next) => {
+                            return Err(ParseError::UnexpectedArgument {
+                                argument: name,
+                                token: word.to_string(),
+                                found: next,
+                            })
+                        }
+                        Some(next) => next,
+                        None => {
+                            return Err(ParseError::MissingValue {
+                                argument: name,
+                                token: word.to_string(),
+                            })
+                        }
+                    },
+                };
+                Value::from(next)
+            }
+            "vector" => {
+                let mut value = attached.into_iter().collect::<Vec<_>>();
+                while let Some(next) = env_arguments.peek() {
+                    if self.is_argument(next) || self.subcommands.contains_key(next.as_str()) {
+                        break;
                     }
+                    value.push(env_arguments.next().unwrap());
+                }
+                if value.is_empty() {
+                    return Err(ParseError::EmptyVector {
+                        argument: name,
+                        token: word.to_string(),
+                    });
+                }
+                Value::from(value)
+            }
+            _ => unreachable!(),
+        };
+        let value = match (argument.get_repeat_policy(), parsed.arguments.remove(&name)) {
+            (RepeatPolicy::Append, Some(previous)) if argument.get_type() != "count" => {
+                let mut vector = previous.into_vec();
+                vector.extend(value.into_vec());
+                Value::from(vector)
+            }
+            _ => argument.normalize_value(value),
+        };
+        parsed.insert(name, value, ValueSource::CommandLine { index });
+        Ok(())
+    }
+    /// name of the environment variable an argument falls back to, if any
+    pub fn env_variable(&self, argument: &Argument) -> Option<String> {
+        match argument.get_env() {
+            Some(variable) => Some(variable.to_string()),
+            None if !self.env_prefix.is_empty() => Some(format!(
+                "{}_{}",
+                self.env_prefix,
+                argument
+                    .name
+                    .trim_start_matches('-')
+                    .replace('-', "_")
+                    .to_uppercase()
+            )),
+            None => None,
+        }
+    }
+    /// convert text from outside the command line to the value of the argument  
+    /// flags accept 1/0, true/false, yes/no and on/off, counts accept a number,
+    /// vectors are split on the separator
+    pub(crate) fn value_from_text(&self, argument: &Argument, text: &str) -> Option<Value> {
+        let separator = self.env_separator.unwrap_or(',');
+        match (argument.get_type(), argument.get_arity()) {
+            ("flag", _) => match text.to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Some(Value::from(true)),
+                "0" | "false" | "no" | "off" | "" => Some(Value::from(false)),
+                _ => None,
+            },
+            ("count", _) => text.trim().parse::<usize>().ok().map(Value::from),
+            ("vector", _) | ("positional", Arity::OneOrMore) => {
+                let vector = text
+                    .split(separator)
+                    .filter(|f| !f.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>();
+                if vector.is_empty() {
+                    None
                 } else {
-                    continue;
+                    Some(Value::from(vector))
+                }
+            }
+            _ => Some(Value::from(text)),
+        }
+    }
+    /// read every argument missing from the command line from its environment variable
+    fn fill_environment(
+        &self,
+        parsed: &mut ParsedArguments,
+        env: &HashMap<String, String>,
+    ) -> Result<(), ParseError> {
+        for name in &self.order {
+            let argument = &self.arguments[name];
+            if parsed.contains(name) {
+                continue;
+            }
+            let variable = match self.env_variable(argument) {
+                Some(variable) => variable,
+                None => continue,
+            };
+            if let Some(text) = env.get(&variable) {
+                let value = match self.value_from_text(argument, text) {
+                    Some(value) => value,
+                    None => {
+                        return Err(ParseError::InvalidEnvironmentValue {
+                            argument: name.clone(),
+                            variable,
+                            value: text.clone(),
+                        })
+                    }
+                };
+                parsed.insert(
+                    name.clone(),
+                    argument.normalize_value(value),
+                    ValueSource::Environment { variable },
+                );
+            }
+        }
+        Ok(())
+    }
+    /// read every argument still missing from its key in the section of the configuration file  
+    /// the entries were checked by `check_config` beforehand
+    fn fill_config(&self, parsed: &mut ParsedArguments, config: &ConfigFile, section: &str) {
+        for entry in config.entries(section) {
+            if parsed.contains(&entry.key) {
+                continue;
+            }
+            let argument = &self.arguments[&entry.key];
+            if let Some(value) = self.value_from_text(argument, &entry.value) {
+                parsed.insert(
+                    entry.key.clone(),
+                    argument.normalize_value(value),
+                    ValueSource::ConfigFile {
+                        path: config.path.clone(),
+                        line: entry.line,
+                    },
+                );
+            }
+        }
+    }
+    /// give every absent argument that has a default value its default
+    fn fill_defaults(&self, parsed: &mut ParsedArguments) {
+        for argument in self.arguments.values() {
+            if let Some(default) = argument.get_default_value() {
+                if !parsed.contains(&argument.name) {
+                    parsed.insert(
+                        argument.name.clone(),
+                        argument.normalize_value(default.clone()),
+                        ValueSource::Default,
+                    );
+                }
+            }
+        }
+    }
+    /// fill the positionals in order of their index  
+    /// optional and one-or-more positionals leave enough values for the positionals after them  
+    /// values from `terminated` onwards came after `--`, the ones left over are returned
+    /// instead of being reported as unexpected  
+    /// each value comes with its index, one-or-more positionals take the index of their first value
+    fn assign_positionals(
+        &self,
+        values: Vec<(usize, String)>,
+        terminated: usize,
+        raw: Option<&[OsString]>,
+        parsed: &mut ParsedArguments,
+    ) -> Result<Vec<String>, ParseError> {
+        let total = values.len();
+        let mut values = values.into_iter();
+        for (position, name) in self.positionals.iter().enumerate() {
+            let remaining = values.len();
+            let needed_after = self.positionals[position + 1..]
+                .iter()
+                .map(|f| self.arguments[f].get_arity().minimum())
+                .sum::<usize>();
+            let available = remaining.saturating_sub(needed_after);
+            let arity = self.arguments[name].get_arity();
+            let take = match arity {
+                Arity::One => remaining.min(1),
+                Arity::Optional => available.min(1),
+                Arity::OneOrMore => available.max(remaining.min(1)),
+            };
+            if take == 0 {
+                continue;
+            }
+            let (indices, mut words): (Vec<_>, Vec<_>) = values.by_ref().take(take).unzip();
+            let value = match arity {
+                Arity::OneOrMore => Value::from(words),
+                _ => Value::from(words.swap_remove(0)),
+            };
+            let source = ValueSource::CommandLine { index: indices[0] };
+            parsed.insert(name.clone(), value, source);
+            if let Some(raw) = raw {
+                let values = indices.iter().map(|f| raw[*f].clone()).collect();
+                parsed.set_raw(name, values, false);
+            }
+        }
+        let mut leftover = values.map(|f| f.1).collect::<Vec<_>>();
+        if total - leftover.len() < terminated {
+            let token = leftover.swap_remove(0);
+            let names = self.subcommand_order.iter().map(String::as_str);
+            return Err(ParseError::UnexpectedPositional {
+                suggestions: suggestions::suggest(&token, names),
+                token,
+            });
+        }
+        Ok(leftover)
+    }
+    /// check every word and every element of a vector against the possible values
+    /// and the validator of its argument
+    fn validate_values(&self, parsed: &ParsedArguments) -> Result<(), ParseError> {
+        for name in &self.order {
+            let argument = &self.arguments[name];
+            let values = match parsed.get_value(name) {
+                Some(Value::Word(word)) => std::slice::from_ref(word),
+                Some(Value::Vector(vector)) => vector.as_slice(),
+                _ => continue,
+            };
+            let choices = argument.get_possible_values();
+            for value in values {
+                if !choices.is_empty() && !choices.contains(value) {
+                    return Err(ParseError::InvalidChoice {
+                        argument: name.clone(),
+                        value: value.clone(),
+                        choices: choices.to_vec(),
+                        suggestions: suggestions::suggest(
+                            value,
+                            choices.iter().map(String::as_str),
+                        ),
+                    });
+                }
+                if let Some(validator) = argument.get_validator() {
+                    if let Err(message) = validator.validate(value) {
+                        return Err(ParseError::InvalidValue {
+                            argument: name.clone(),
+                            value: value.clone(),
+                            message,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    /// enforce conflicts, requirements and conditional requirements between arguments  
+    /// only given arguments count, defaults are not filled in yet
+    fn check_relations(&self, parsed: &ParsedArguments) -> Result<(), ParseError> {
+        for name in &self.order {
+            let argument = &self.arguments[name];
+            let given = parsed.contains(name);
+            if given {
+                if let Some(other) = argument.get_conflicts().iter().find(|f| parsed.contains(f)) {
+                    return Err(ParseError::Conflict {
+                        argument: name.clone(),
+                        other: other.clone(),
+                    });
+                }
+                if let Some(required) = argument.get_requires().iter().find(|f| !parsed.contains(f))
+                {
+                    return Err(ParseError::MissingDependency {
+                        argument: name.clone(),
+                        required: required.clone(),
+                    });
+                }
+                continue;
+            }
+            for (other, value) in argument.get_required_if() {
+                let matches = match (parsed.get_value(other), value) {
+                    (Some(Value::Vector(vector)), Value::Word(word)) => vector.contains(word),
+                    (Some(given), value) => given == value,
+                    (None, _) => false,
+                };
+                if matches {
+                    return Err(ParseError::MissingRequiredIf {
+                        argument: name.clone(),
+                        other: other.clone(),
+                        value: value.to_string(),
+                    });
                 }
             }
+            let unless = argument.get_required_unless();
+            if !unless.is_empty() && !unless.iter().any(|f| parsed.contains(f)) {
+                return Err(ParseError::MissingRequiredUnless {
+                    argument: name.clone(),
+                    others: unless.to_vec(),
+                });
+            }
+        }
+        Ok(())
+    }
+    /// enforce exclusive and required groups and record which arguments of each group were given
+    fn check_groups(&self, parsed: &mut ParsedArguments) -> Result<(), ParseError> {
+        for group in &self.groups {
+            let given = group
+                .get_arguments()
+                .iter()
+                .filter(|f| parsed.contains(f))
+                .cloned()
+                .collect::<Vec<_>>();
+            if group.is_exclusive() && given.len() > 1 {
+                return Err(ParseError::GroupConflict {
+                    group: group.name.clone(),
+                    arguments: given,
+                });
+            }
+            if group.is_required() && given.is_empty() {
+                return Err(ParseError::GroupMissing {
+                    group: group.name.clone(),
+                    arguments: group.get_arguments().to_vec(),
+                });
+            }
+            parsed.set_group(&group.name, given);
+        }
+        Ok(())
+    }
+    /// report every required argument missing from the parsed arguments at once
+    fn check_required(&self, parsed: &ParsedArguments) -> Result<(), ParseError> {
+        let missing = self
+            .order
+            .iter()
+            .map(|f| &self.arguments[f])
+            .filter(|f| f.is_required() && !parsed.contains(&f.name))
+            .map(|f| {
+                let invocators = f.invocators().into_iter().map(str::to_string).collect();
+                (f.name.clone(), invocators)
+            })
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ParseError::MissingRequired { missing })
         }
-        parsed
     }
 }
 
@@ -368,4 +1354,1092 @@ mod parser_tests {
             .unwrap()
             .eq(&super::Value::from(true)));
     }
+    #[test]
+    fn try_parsing_errors() {
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("sleep")
+                .invoke_with("-s"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("my-name")
+                .invoke_with("-n"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("colors")
+                .invoke_with("-c"),
+        );
+        assert_eq!(
+            parser.try_parse_arguments(&["--sleep"]).unwrap_err(),
+            super::ParseError::UnrecognizedArgument {
+                token: "--sleep".to_string(),
+                suggestions: vec![]
+            }
+        );
+        assert_eq!(
+            parser.try_parse_arguments(&["-n"]).unwrap_err(),
+            super::ParseError::MissingValue {
+                argument: "my-name".to_string(),
+                token: "-n".to_string()
+            }
+        );
+        assert_eq!(
+            parser.try_parse_arguments(&["-n", "-s"]).unwrap_err(),
+            super::ParseError::UnexpectedArgument {
+                argument: "my-name".to_string(),
+                token: "-n".to_string(),
+                found: "-s".to_string()
+            }
+        );
+        assert_eq!(
+            parser.try_parse_arguments(&["-c", "-s"]).unwrap_err(),
+            super::ParseError::EmptyVector {
+                argument: "colors".to_string(),
+                token: "-c".to_string()
+            }
+        );
+        let error = parser.try_parse_arguments(&["-sn"]).unwrap_err();
+        assert_eq!(error.argument(), Some("my-name"));
+        assert_eq!(error.token(), Some("-sn"));
+        assert!(parser.try_parse_arguments(&["-s", "-c", "red"]).is_ok());
+    }
+    #[test]
+    #[should_panic(expected = "unrecognized argument found: --sleep")]
+    fn parsing_panic() {
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(super::Argument::with_type("flag").name("sleep"));
+        parser.parse_arguments(&["--sleep"]);
+    }
+    #[test]
+    fn required_arguments() {
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("dragon-colors")
+                .invoke_with("-d")
+                .required(true),
+        );
+        parser.add_argument(super::Argument::with_type("flag").name("sleep"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("my-name")
+                .invoke_with("--my-name")
+                .required(true),
+        );
+        assert_eq!(
+            parser.try_parse_arguments(&["sleep"]).unwrap_err(),
+            super::ParseError::MissingRequired {
+                missing: vec![
+                    (
+                        "dragon-colors".to_string(),
+                        vec!["-d".to_string(), "dragon-colors".to_string()]
+                    ),
+                    (
+                        "my-name".to_string(),
+                        vec!["--my-name".to_string(), "my-name".to_string()]
+                    ),
+                ]
+            }
+        );
+        assert!(parser
+            .try_parse_arguments(&["-d", "red", "--my-name", "Jondo"])
+            .is_ok());
+    }
+    #[test]
+    fn positional_arguments() {
+        let mut parser = super::Parser::with_capacity(4);
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("verbose")
+                .invoke_with("-v"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("positional")
+                .name("command")
+                .index(0),
+        );
+        parser.add_argument(
+            super::Argument::with_type("positional")
+                .name("destination")
+                .index(2),
+        );
+        parser.add_argument(
+            super::Argument::with_type("positional")
+                .name("sources")
+                .index(1)
+                .arity(super::Arity::OneOrMore),
+        );
+        let parsed = parser.parse_arguments(&["build", "-v", "src/", "lib/", "out/"]);
+        assert_eq!(
+            parsed.get_value("command"),
+            Some(&super::Value::from("build"))
+        );
+        assert_eq!(
+            parsed.get_value("sources"),
+            Some(&super::Value::from(vec!["src/", "lib/"]))
+        );
+        assert_eq!(
+            parsed.get_value("destination"),
+            Some(&super::Value::from("out/"))
+        );
+        assert_eq!(parsed.get_value("verbose"), Some(&super::Value::from(true)));
+        assert_eq!(
+            parser.try_parse_arguments(&["build", "out/"]).unwrap_err(),
+            super::ParseError::MissingRequired {
+                missing: vec![("destination".to_string(), vec![])]
+            }
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["build", "-q", "out/"])
+                .unwrap_err(),
+            super::ParseError::UnrecognizedArgument {
+                token: "-q".to_string(),
+                suggestions: vec![]
+            }
+        );
+    }
+    #[test]
+    #[should_panic(expected = "duplicate argument name found: input")]
+    fn positional_name_taken_panic() {
+        let mut parser = super::Parser::with_capacity(2);
+        parser.add_argument(super::Argument::with_type("positional").name("input"));
+        parser.add_argument(super::Argument::with_type("flag").name("input"));
+    }
+    #[test]
+    fn optional_positional() {
+        let mut parser = super::Parser::with_capacity(2);
+        parser.add_argument(super::Argument::with_type("positional").name("input"));
+        parser.add_argument(
+            super::Argument::with_type("positional")
+                .name("output")
+                .arity(super::Arity::Optional),
+        );
+        let parsed = parser.parse_arguments(&["-"]);
+        assert_eq!(parsed.get_value("input"), Some(&super::Value::from("-")));
+        assert!(!parsed.contains("output"));
+        assert_eq!(
+            parser.try_parse_arguments(&["a", "b", "c"]).unwrap_err(),
+            super::ParseError::UnexpectedPositional {
+                token: "c".to_string(),
+                suggestions: vec![]
+            }
+        );
+    }
+    #[test]
+    fn subcommands() {
+        let mut migrate = super::Parser::with_capacity(1);
+        migrate.add_argument(super::Argument::with_type("flag").name("--dry-run"));
+        let mut db = super::Parser::with_capacity(1);
+        db.add_argument(
+            super::Argument::with_type("word")
+                .name("--url")
+                .required(true),
+        );
+        db.add_subcommand("migrate", migrate);
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(super::Argument::with_type("flag").name("--verbose"));
+        parser.add_subcommand("db", db);
+        let parsed =
+            parser.parse_arguments(&["--verbose", "db", "--url", "x", "migrate", "--dry-run"]);
+        assert!(parsed.contains("--verbose"));
+        assert_eq!(parsed.subcommand_path(), vec!["db", "migrate"]);
+        let (name, db_parsed) = parsed.subcommand().unwrap();
+        assert_eq!(name, "db");
+        assert_eq!(db_parsed.get_value("--url"), Some(&super::Value::from("x")));
+        assert_eq!(db_parsed.subcommand_name(), Some("migrate"));
+        assert!(db_parsed.subcommand().unwrap().1.contains("--dry-run"));
+        let error = parser
+            .try_parse_arguments(&["db", "--url", "x", "migrate", "--wet-run"])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            super::ParseError::Subcommand {
+                path: vec!["db".to_string(), "migrate".to_string()],
+                error: Box::new(super::ParseError::UnrecognizedArgument {
+                    token: "--wet-run".to_string(),
+                    suggestions: vec![]
+                })
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "in subcommand `db migrate`: unrecognized argument found: --wet-run"
+        );
+        assert!(parser.try_parse_arguments(&["db", "migrate"]).is_err());
+    }
+    #[test]
+    fn subcommand_names_after_values() {
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(super::Argument::with_type("vector").name("--tags"));
+        parser.add_subcommand("build", super::Parser::new());
+        let parsed = parser.parse_arguments(&["--tags", "a", "build"]);
+        assert_eq!(
+            parsed.get_value("--tags"),
+            Some(&super::Value::from(vec!["a"]))
+        );
+        assert_eq!(parsed.subcommand_name(), Some("build"));
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(super::Argument::with_type("word").name("--message"));
+        parser.add_subcommand("build", super::Parser::new());
+        let parsed = parser.parse_arguments(&["--message", "build"]);
+        assert_eq!(
+            parsed.get_value("--message"),
+            Some(&super::Value::from("build"))
+        );
+        assert_eq!(parsed.subcommand_name(), None);
+    }
+    #[test]
+    fn default_values() {
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("level")
+                .invoke_with("-l")
+                .default_value("info"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("colors")
+                .default_value(vec!["red", "blue"]),
+        );
+        parser.add_argument(
+            super::Argument::with_type("positional")
+                .name("output")
+                .arity(super::Arity::Optional)
+                .default_value("out/"),
+        );
+        let parsed = parser.parse_arguments(&["-l", "debug"]);
+        assert_eq!(
+            parsed.get_value("level"),
+            Some(&super::Value::from("debug"))
+        );
+        assert_eq!(
+            parsed.get_value("colors"),
+            Some(&super::Value::from(vec!["red", "blue"]))
+        );
+        assert_eq!(
+            parsed.get_value("output"),
+            Some(&super::Value::from("out/"))
+        );
+    }
+    #[test]
+    #[should_panic(expected = "default value of level does not match its type: word")]
+    fn default_value_type_panic() {
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("level")
+                .default_value(vec!["info"]),
+        );
+    }
+    #[test]
+    fn environment_fallback() {
+        let mut parser = super::Parser::with_capacity(4)
+            .env_prefix("APP")
+            .env_separator(':');
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("port")
+                .invoke_with("-p")
+                .env("APP_PORT")
+                .required(true),
+        );
+        parser.add_argument(super::Argument::with_type("flag").name("--dry-run"));
+        parser.add_argument(super::Argument::with_type("vector").name("paths"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("host")
+                .default_value("localhost"),
+        );
+        let mut env = super::HashMap::new();
+        env.insert("APP_PORT".to_string(), "8080".to_string());
+        env.insert("APP_DRY_RUN".to_string(), "Yes".to_string());
+        env.insert("APP_PATHS".to_string(), "/bin:/usr/bin".to_string());
+        let parsed = parser.parse_arguments_with_env(&["-p", "9090"], &env);
+        assert_eq!(parsed.get_value("port"), Some(&super::Value::from("9090")));
+        assert_eq!(
+            parsed.get_value("--dry-run"),
+            Some(&super::Value::from(true))
+        );
+        assert_eq!(
+            parsed.get_value("paths"),
+            Some(&super::Value::from(vec!["/bin", "/usr/bin"]))
+        );
+        assert_eq!(
+            parsed.get_value("host"),
+            Some(&super::Value::from("localhost"))
+        );
+        let parsed = parser.parse_arguments_with_env(&["--no-dry-run"], &env);
+        assert_eq!(parsed.get_value("port"), Some(&super::Value::from("8080")));
+        assert_eq!(
+            parsed.get_value("--dry-run"),
+            Some(&super::Value::from(false))
+        );
+        env.insert("APP_DRY_RUN".to_string(), "maybe".to_string());
+        assert_eq!(
+            parser
+                .try_parse_arguments_with_env(&["-p", "1"], &env)
+                .unwrap_err(),
+            super::ParseError::InvalidEnvironmentValue {
+                argument: "--dry-run".to_string(),
+                variable: "APP_DRY_RUN".to_string(),
+                value: "maybe".to_string()
+            }
+        );
+    }
+    #[test]
+    fn attached_values() {
+        let mut parser = super::Parser::with_capacity(4);
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("my-name")
+                .invoke_with("--my-name")
+                .invoke_with("-n"),
+        );
+        parser.add_argument(super::Argument::with_type("word").name("--offset"));
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("colors")
+                .invoke_with("-c"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("sleep")
+                .invoke_with("--sleep")
+                .invoke_with("-s"),
+        );
+        let parsed = parser.parse_arguments(&["--my-name=Jondo", "--offset=-5", "-cred", "blue"]);
+        assert_eq!(
+            parsed.get_value("my-name"),
+            Some(&super::Value::from("Jondo"))
+        );
+        assert_eq!(
+            parsed.get_value("--offset"),
+            Some(&super::Value::from("-5"))
+        );
+        assert_eq!(
+            parsed.get_value("colors"),
+            Some(&super::Value::from(vec!["red", "blue"]))
+        );
+        let parsed = parser.parse_arguments(&["-nJondo", "-c=-1", "-s"]);
+        assert_eq!(
+            parsed.get_value("my-name"),
+            Some(&super::Value::from("Jondo"))
+        );
+        assert_eq!(
+            parsed.get_value("colors"),
+            Some(&super::Value::from(vec!["-1"]))
+        );
+        assert_eq!(parsed.get_value("sleep"), Some(&super::Value::from(true)));
+        assert_eq!(
+            parser.try_parse_arguments(&["--no-sleep=yes"]).unwrap_err(),
+            super::ParseError::FlagWithValue {
+                argument: "sleep".to_string(),
+                token: "--no-sleep=yes".to_string()
+            }
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["-n", "--offset=3"])
+                .unwrap_err(),
+            super::ParseError::UnexpectedArgument {
+                argument: "my-name".to_string(),
+                token: "-n".to_string(),
+                found: "--offset=3".to_string()
+            }
+        );
+    }
+    #[test]
+    fn terminator() {
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(super::Argument::with_type("flag").name("-r"));
+        parser.add_argument(super::Argument::with_type("flag").name("-f"));
+        parser.add_argument(super::Argument::with_type("vector").name("--files"));
+        let parsed = parser.parse_arguments(&["--files", "a", "--", "-rf", "--files"]);
+        assert_eq!(
+            parsed.get_value("--files"),
+            Some(&super::Value::from(vec!["a"]))
+        );
+        assert!(!parsed.contains("-r"));
+        assert_eq!(parsed.trailing(), &["-rf", "--files"]);
+        assert!(parser.parse_arguments(&["--"]).trailing().is_empty());
+        parser.add_argument(super::Argument::with_type("positional").name("target"));
+        let parsed = parser.parse_arguments(&["-r", "--", "-rf", "child", "--flag"]);
+        assert_eq!(parsed.get_value("target"), Some(&super::Value::from("-rf")));
+        assert_eq!(parsed.trailing(), &["child", "--flag"]);
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["one", "two", "--", "three"])
+                .unwrap_err(),
+            super::ParseError::UnexpectedPositional {
+                token: "two".to_string(),
+                suggestions: vec![]
+            }
+        );
+    }
+    #[test]
+    fn validated_values() {
+        let mut parser = super::Parser::with_capacity(2);
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--level")
+                .possible_values(&["debug", "info"]),
+        );
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("--ports")
+                .validator(|f| match f.parse::<u16>() {
+                    Ok(_) => Ok(()),
+                    Err(error) => Err(error.to_string()),
+                }),
+        );
+        let parsed = parser.parse_arguments(&["--level", "info", "--ports", "80", "443"]);
+        assert_eq!(parsed.get_many::<u16>("--ports"), Ok(vec![80, 443]));
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--level", "banana"])
+                .unwrap_err(),
+            super::ParseError::InvalidChoice {
+                argument: "--level".to_string(),
+                value: "banana".to_string(),
+                choices: vec!["debug".to_string(), "info".to_string()],
+                suggestions: vec![]
+            }
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--ports", "80", "http"])
+                .unwrap_err()
+                .to_string(),
+            "invalid value \"http\" for --ports: invalid digit found in string"
+        );
+    }
+    #[test]
+    #[should_panic(expected = "default value of --level is not one of its possible values: zzz")]
+    fn default_value_choice_panic() {
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--level")
+                .possible_values(&["a", "b"])
+                .default_value("zzz"),
+        );
+    }
+    #[test]
+    fn default_value_validated() {
+        let port = super::Argument::with_type("word")
+            .name("--port")
+            .validator(|f| f.parse::<u16>().map(|_| ()).map_err(|f| f.to_string()))
+            .default_value("http");
+        assert_eq!(
+            super::Parser::new().check_argument(&port),
+            Err("default value of --port is invalid: invalid digit found in string".to_string())
+        );
+    }
+    #[test]
+    fn unwind_safe() {
+        fn unwind_safe<T: std::panic::UnwindSafe + std::panic::RefUnwindSafe>() {}
+        unwind_safe::<super::Argument>();
+        unwind_safe::<super::Parser>();
+    }
+    #[test]
+    fn counting_flags() {
+        let mut parser = super::Parser::with_capacity(2);
+        parser.add_argument(
+            super::Argument::with_type("count")
+                .name("verbose")
+                .invoke_with("--verbose")
+                .invoke_with("-v"),
+        );
+        parser.add_argument(super::Argument::with_type("flag").name("-q"));
+        let parsed = parser.parse_arguments(&["-v", "-vqv", "--verbose"]);
+        assert_eq!(
+            parsed.get_value("verbose"),
+            Some(&super::Value::from(4usize))
+        );
+        assert_eq!(parsed.get::<u8>("verbose"), Ok(4));
+        assert_eq!(parsed.get_value("-q"), Some(&super::Value::from(true)));
+        let parsed = parser.parse_arguments(&["-vvv", "--no-verbose", "-v"]);
+        assert_eq!(
+            parsed.get_value("verbose"),
+            Some(&super::Value::from(1usize))
+        );
+        let parsed = parser.parse_arguments(&["-vv", "--no-verbose"]);
+        assert_eq!(
+            parsed.get_value("verbose"),
+            Some(&super::Value::from(0usize))
+        );
+        assert!(parser.try_parse_arguments(&["--verbose=3"]).is_err());
+    }
+    #[test]
+    fn repeated_arguments() {
+        let mut parser = super::Parser::with_capacity(3);
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("include")
+                .invoke_with("-I")
+                .on_repeat(super::RepeatPolicy::Append)
+                .default_value("/usr/include"),
+        );
+        parser.add_argument(super::Argument::with_type("word").name("-o"));
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("--once")
+                .on_repeat(super::RepeatPolicy::Error),
+        );
+        let parsed = parser.parse_arguments(&["-I", "a", "-o", "x", "-Ib", "-o", "y", "--once"]);
+        assert_eq!(
+            parsed.get_value("include"),
+            Some(&super::Value::from(vec!["a", "b"]))
+        );
+        assert_eq!(parsed.get_value("-o"), Some(&super::Value::from("y")));
+        assert_eq!(parsed.occurrences("include"), 2);
+        assert_eq!(parsed.occurrences("-o"), 2);
+        assert_eq!(parsed.occurrences("--once"), 1);
+        assert_eq!(parsed.occurrences("missing"), 0);
+        let parsed = parser.parse_arguments(&["-I", "a"]);
+        assert_eq!(
+            parsed.get_value("include"),
+            Some(&super::Value::from(vec!["a"]))
+        );
+        let parsed = parser.parse_arguments(&["-o", "x"]);
+        assert_eq!(
+            parsed.get_value("include"),
+            Some(&super::Value::from(vec!["/usr/include"]))
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--once", "--no-once"])
+                .unwrap_err(),
+            super::ParseError::RepeatedArgument {
+                argument: "--once".to_string(),
+                token: "--no-once".to_string()
+            }
+        );
+    }
+    #[test]
+    fn argument_relations() {
+        let mut parser = super::Parser::with_capacity(5);
+        parser.add_argument(super::Argument::with_type("flag").name("--json"));
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("--table")
+                .conflicts_with("--json"),
+        );
+        parser.add_argument(super::Argument::with_type("word").name("--cert"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--key")
+                .requires("--cert"),
+        );
+        parser.add_argument(super::Argument::with_type("word").name("--mode"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--port")
+                .required_if("--mode", "server")
+                .required_unless("--json"),
+        );
+        assert!(parser
+            .try_parse_arguments(&["--json", "--key", "k", "--cert", "c"])
+            .is_ok());
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--json", "--table"])
+                .unwrap_err(),
+            super::ParseError::Conflict {
+                argument: "--table".to_string(),
+                other: "--json".to_string()
+            }
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--json", "--key", "k"])
+                .unwrap_err(),
+            super::ParseError::MissingDependency {
+                argument: "--key".to_string(),
+                required: "--cert".to_string()
+            }
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--json", "--mode", "server"])
+                .unwrap_err(),
+            super::ParseError::MissingRequiredIf {
+                argument: "--port".to_string(),
+                other: "--mode".to_string(),
+                value: "server".to_string()
+            }
+        );
+        assert_eq!(
+            parser.try_parse_arguments(&["--table"]).unwrap_err(),
+            super::ParseError::MissingRequiredUnless {
+                argument: "--port".to_string(),
+                others: vec!["--json".to_string()]
+            }
+        );
+    }
+    #[test]
+    #[should_panic(expected = "unknown argument referenced by --table: --json")]
+    fn unknown_relation_panic() {
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("--table")
+                .conflicts_with("--json"),
+        );
+    }
+    #[test]
+    fn argument_groups() {
+        let mut parser = super::Parser::with_capacity(4);
+        parser.add_argument(super::Argument::with_type("flag").name("json"));
+        parser.add_argument(super::Argument::with_type("flag").name("yaml"));
+        parser.add_argument(super::Argument::with_type("flag").name("table"));
+        parser.add_argument(super::Argument::with_type("word").name("--out"));
+        parser.add_group(
+            super::ArgGroup::new("output")
+                .args(&["json", "yaml", "table"])
+                .exclusive()
+                .required(),
+        );
+        let parsed = parser.parse_arguments(&["yaml", "--out", "x"]);
+        assert_eq!(parsed.chosen("output"), Some("yaml"));
+        assert_eq!(parsed.chosen_all("output"), &["yaml"]);
+        assert_eq!(parsed.chosen("missing"), None);
+        assert_eq!(
+            parser.try_parse_arguments(&["json", "table"]).unwrap_err(),
+            super::ParseError::GroupConflict {
+                group: "output".to_string(),
+                arguments: vec!["json".to_string(), "table".to_string()]
+            }
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--out", "x"])
+                .unwrap_err()
+                .to_string(),
+            "one argument of output is required: json, yaml, table"
+        );
+    }
+    #[test]
+    fn configuration_file() {
+        let mut migrate = super::Parser::with_capacity(1);
+        migrate.add_argument(super::Argument::with_type("flag").name("dry-run"));
+        let mut parser = super::Parser::with_capacity(3).env_prefix("APP");
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("level")
+                .invoke_with("-l"),
+        );
+        parser.add_argument(super::Argument::with_type("word").name("host"));
+        parser.add_argument(super::Argument::with_type("vector").name("paths"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("user")
+                .default_value("nobody"),
+        );
+        parser.add_subcommand("migrate", migrate);
+        let path = std::env::temp_dir().join(format!(
+            "argumentparser_configuration_file_{}.ini",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "level = warn\nhost = config\npaths = a,b\n[migrate]\ndry-run = on\n",
+        )
+        .unwrap();
+        let mut env = super::HashMap::new();
+        env.insert("APP_HOST".to_string(), "env".to_string());
+        let config = super::ConfigFile::read(&path).unwrap();
+        let parsed = parser.parse_with_config_file(&["-l", "debug", "migrate"], &env, &config);
+        assert_eq!(
+            parsed.get_value("level"),
+            Some(&super::Value::from("debug"))
+        );
+        assert_eq!(parsed.get_value("host"), Some(&super::Value::from("env")));
+        assert_eq!(
+            parsed.get_value("paths"),
+            Some(&super::Value::from(vec!["a", "b"]))
+        );
+        assert_eq!(
+            parsed.get_value("user"),
+            Some(&super::Value::from("nobody"))
+        );
+        let (_, migrate) = parsed.subcommand().unwrap();
+        assert_eq!(
+            migrate.get_value("dry-run"),
+            Some(&super::Value::from(true))
+        );
+        let parsed = parser.parse_with_config(&["-l", "info"], &path);
+        assert_eq!(parsed.get_value("level"), Some(&super::Value::from("info")));
+        std::fs::write(&path, "level = warn\n\n[migrate]\nwet-run = yes\n").unwrap();
+        assert_eq!(
+            parser
+                .try_parse_with_config(&["-l", "info"], &path)
+                .unwrap_err(),
+            super::ParseError::Config {
+                path: path.clone(),
+                line: 4,
+                message: "unknown key: wet-run".to_string()
+            }
+        );
+        std::fs::write(&path, "[deploy]\n").unwrap();
+        assert_eq!(
+            parser
+                .try_parse_with_config(&["-l", "info"], &path)
+                .unwrap_err()
+                .to_string(),
+            format!("{}:1: unknown section: deploy", path.display())
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            parser.try_parse_with_config(&["-l", "info"], &path),
+            Err(super::ParseError::ConfigRead { .. })
+        ));
+    }
+    #[test]
+    fn configuration_values_checked() {
+        let mut parser = super::Parser::with_capacity(2);
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("level")
+                .possible_values(&["a", "b"]),
+        );
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("port")
+                .validator(|f| f.parse::<u16>().map(|_| ()).map_err(|f| f.to_string())),
+        );
+        let path = std::path::Path::new("x.ini");
+        let env = super::HashMap::new();
+        let config = super::ConfigFile::parse(
+            path,
+            "level = a,zzz
+",
+        )
+        .unwrap();
+        assert_eq!(
+            parser
+                .try_parse_with_config_file::<&str>(&[], &env, &config)
+                .unwrap_err()
+                .to_string(),
+            "x.ini:1: invalid value \"zzz\" for level, possible values are: a, b"
+        );
+        let config = super::ConfigFile::parse(
+            path,
+            "level = b
+port = http
+",
+        )
+        .unwrap();
+        assert_eq!(
+            parser
+                .try_parse_with_config_file::<&str>(&[], &env, &config)
+                .unwrap_err(),
+            super::ParseError::Config {
+                path: path.to_path_buf(),
+                line: 2,
+                message: "invalid value \"http\" for port: invalid digit found in string"
+                    .to_string()
+            }
+        );
+    }
+    #[test]
+    fn value_sources() {
+        let mut build = super::Parser::with_capacity(1);
+        build.add_argument(super::Argument::with_type("positional").name("target"));
+        let mut parser = super::Parser::with_capacity(4).env_prefix("APP");
+        parser.add_argument(super::Argument::with_type("word").name("--level"));
+        parser.add_argument(super::Argument::with_type("word").name("--host"));
+        parser.add_argument(super::Argument::with_type("word").name("--port"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--user")
+                .default_value("nobody"),
+        );
+        parser.add_subcommand("build", build);
+        let config = super::ConfigFile::parse(
+            std::path::Path::new("app.ini"),
+            "--host = config\n--port = 80\n",
+        )
+        .unwrap();
+        let mut env = super::HashMap::new();
+        env.insert("APP_HOST".to_string(), "env".to_string());
+        let parsed = parser
+            .try_parse_with_config_file(
+                &["--level", "x", "--level=y", "build", "all"],
+                &env,
+                &config,
+            )
+            .unwrap();
+        assert_eq!(
+            parsed.source("--level"),
+            Some(&super::ValueSource::CommandLine { index: 2 })
+        );
+        assert_eq!(
+            parsed.source("--host"),
+            Some(&super::ValueSource::Environment {
+                variable: "APP_HOST".to_string()
+            })
+        );
+        assert_eq!(
+            parsed.source("--port").map(ToString::to_string),
+            Some("app.ini:2".to_string())
+        );
+        assert_eq!(parsed.source("--user"), Some(&super::ValueSource::Default));
+        let (_, build) = parsed.subcommand().unwrap();
+        assert_eq!(
+            build.source("target"),
+            Some(&super::ValueSource::CommandLine { index: 4 })
+        );
+        assert_eq!(build.source("missing"), None);
+    }
+    #[test]
+    fn suggestions() {
+        let mut parser = super::Parser::with_capacity(2);
+        parser.add_argument(super::Argument::with_type("vector").name("--dragon-colors"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--level")
+                .possible_values(&["debug", "info", "warn"]),
+        );
+        parser.add_subcommand("build", super::Parser::new());
+        parser.add_subcommand("bench", super::Parser::new());
+        let error = parser
+            .try_parse_arguments(&["--dragon-color=red"])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            super::ParseError::UnrecognizedArgument {
+                token: "--dragon-color=red".to_string(),
+                suggestions: vec!["--dragon-colors".to_string()]
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "unrecognized argument found: --dragon-color=red (did you mean --dragon-colors?)"
+        );
+        let error = parser.try_parse_arguments(&["biuld"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unexpected positional argument: biuld (did you mean build?)"
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--level", "wran"])
+                .unwrap_err(),
+            super::ParseError::InvalidChoice {
+                argument: "--level".to_string(),
+                value: "wran".to_string(),
+                choices: vec!["debug".to_string(), "info".to_string(), "warn".to_string()],
+                suggestions: vec!["warn".to_string()]
+            }
+        );
+    }
+    #[test]
+    fn abbreviations() {
+        let mut parser = super::Parser::with_capacity(4).allow_abbreviations(true);
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("dragon-colors")
+                .invoke_with("--dragon-colors")
+                .invoke_with("--dragon-colours"),
+        );
+        parser.add_argument(super::Argument::with_type("word").name("--dragon-name"));
+        parser.add_argument(super::Argument::with_type("flag").name("--sleep"));
+        parser.add_argument(super::Argument::with_type("flag").name("--no-cache"));
+        let parsed = parser
+            .try_parse_arguments(&["--dragon-c", "red", "--dragon-n=smaug", "--no-sl", "--no-c"])
+            .unwrap();
+        assert_eq!(
+            parsed.get_value("dragon-colors"),
+            Some(&super::Value::from(vec!["red"]))
+        );
+        assert_eq!(
+            parsed.get_value("--dragon-name"),
+            Some(&super::Value::from("smaug"))
+        );
+        assert_eq!(
+            parsed.get_value("--sleep"),
+            Some(&super::Value::from(false))
+        );
+        assert_eq!(
+            parsed.get_value("--no-cache"),
+            Some(&super::Value::from(true))
+        );
+        let error = parser.try_parse_arguments(&["--drag", "red"]).unwrap_err();
+        assert_eq!(
+            error,
+            super::ParseError::AmbiguousArgument {
+                token: "--drag".to_string(),
+                candidates: vec!["--dragon-colors".to_string(), "--dragon-name".to_string()]
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "ambiguous argument --drag, could be: --dragon-colors, --dragon-name"
+        );
+        assert_eq!(
+            parser
+                .try_parse_arguments(&["--no"])
+                .unwrap_err()
+                .to_string(),
+            "ambiguous argument --no, could be: --no-cache, --no-no-cache, --no-sleep"
+        );
+        let mut strict = super::Parser::with_capacity(1);
+        strict.add_argument(super::Argument::with_type("flag").name("--sleep"));
+        assert!(matches!(
+            strict.try_parse_arguments(&["--sl"]),
+            Err(super::ParseError::UnrecognizedArgument { .. })
+        ));
+    }
+    #[cfg(unix)]
+    #[test]
+    fn os_arguments() {
+        use std::ffi::OsString;
+        use std::os::unix::ffi::OsStringExt;
+        let mut parser = super::Parser::with_capacity(4);
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--output")
+                .invoke_with("-o"),
+        );
+        parser.add_argument(super::Argument::with_type("vector").name("--include"));
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--level")
+                .env("OS_ARGUMENTS_LEVEL"),
+        );
+        parser.add_argument(super::Argument::with_type("positional").name("input"));
+        let invalid = |prefix: &[u8]| {
+            let mut bytes = prefix.to_vec();
+            bytes.extend_from_slice(b"caf\xe9");
+            OsString::from_vec(bytes)
+        };
+        let arguments = vec![
+            invalid(b"-o"),
+            OsString::from("--include"),
+            invalid(b""),
+            OsString::from("b"),
+            invalid(b"--level="),
+            invalid(b"in/"),
+        ];
+        let parsed = parser.parse_arguments_os(&arguments);
+        assert_eq!(parsed.get_os("--output"), Some(invalid(b"")));
+        assert_eq!(
+            parsed.get_many_os("--include"),
+            Some(vec![invalid(b""), OsString::from("b")])
+        );
+        assert_eq!(parsed.get_os("--include"), None);
+        assert_eq!(parsed.get_os("--level"), Some(invalid(b"")));
+        assert_eq!(
+            parsed.get_path("input"),
+            Some(std::path::PathBuf::from(invalid(b"in/")))
+        );
+        assert_eq!(
+            parsed.get_value("--output"),
+            Some(&super::Value::from("caf\u{fffd}"))
+        );
+        let parsed = parser.parse_arguments(&["--level", "info", "in"]);
+        assert_eq!(parsed.get_os("--level"), Some(OsString::from("info")));
+        let mut env = super::HashMap::new();
+        env.insert("OS_ARGUMENTS_LEVEL".to_string(), "warn".to_string());
+        let parsed = parser.parse_arguments_os_with_env(&[invalid(b"in/")], &env);
+        assert_eq!(
+            parsed.get_value("--level"),
+            Some(&super::Value::from("warn"))
+        );
+    }
+    #[test]
+    fn program_arguments() {
+        let mut parser = super::Parser::with_capacity(1);
+        parser.add_argument(super::Argument::with_type("word").name("--level"));
+        let parsed = parser
+            .try_parse_argv(&["/usr/local/bin/dragons", "--level", "info"])
+            .unwrap();
+        assert_eq!(
+            parsed.get_value("--level"),
+            Some(&super::Value::from("info"))
+        );
+        assert_eq!(
+            parsed.source("--level"),
+            Some(&super::ValueSource::CommandLine { index: 1 })
+        );
+        assert_eq!(parser.render_usage(), "usage: dragons [options]");
+        parser.try_parse_argv(&["other"]).unwrap();
+        assert_eq!(parser.render_usage(), "usage: dragons [options]");
+        let mut parser = super::Parser::new().program_name("named");
+        assert!(parser.try_parse_argv::<&str>(&[]).is_ok());
+        parser.try_parse_argv(&["dragons"]).unwrap();
+        assert_eq!(parser.render_usage(), "usage: named");
+    }
+    #[test]
+    fn response_files() {
+        let directory = std::env::temp_dir().join(format!(
+            "argumentparser_response_files_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(directory.join("nested")).unwrap();
+        let write = |name: &str, text: &str| std::fs::write(directory.join(name), text).unwrap();
+        write(
+            "main.rsp",
+            "--level 'very high' # comment\n@nested/more.rsp\n",
+        );
+        write("nested/more.rsp", "--tag \"a b\" @@literal\n");
+        write("cycle.rsp", "--tag x\n@cycle.rsp\n");
+        write("broken.rsp", "--tag\n\"open\n");
+        let mut parser = super::Parser::with_capacity(2).response_files(true);
+        parser.add_argument(super::Argument::with_type("word").name("--level"));
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("--tag")
+                .on_repeat(super::RepeatPolicy::Append),
+        );
+        let main = format!("@{}", directory.join("main.rsp").display());
+        let parsed = parser
+            .try_parse_arguments(&[main.as_str(), "--tag", "@@cli", "--", "@rest"])
+            .unwrap();
+        assert_eq!(
+            parsed.get_value("--level"),
+            Some(&super::Value::from("very high"))
+        );
+        assert_eq!(
+            parsed.get_value("--tag"),
+            Some(&super::Value::from(vec!["a b", "@literal", "@cli"]))
+        );
+        assert_eq!(parsed.trailing(), &["@rest"]);
+        let cycle = format!("@{}", directory.join("cycle.rsp").display());
+        assert_eq!(
+            parser.try_parse_arguments(&[&cycle]).unwrap_err(),
+            super::ParseError::ResponseFile {
+                path: directory.join("cycle.rsp"),
+                line: 2,
+                message: format!("{} includes itself", directory.join("cycle.rsp").display())
+            }
+        );
+        let broken = format!("@{}", directory.join("broken.rsp").display());
+        assert_eq!(
+            parser
+                .try_parse_arguments(&[&broken])
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "{}:2: unterminated quote",
+                directory.join("broken.rsp").display()
+            )
+        );
+        let missing = format!("@{}", directory.join("missing.rsp").display());
+        assert!(matches!(
+            parser.try_parse_arguments(&[&missing]),
+            Err(super::ParseError::ResponseFileRead { .. })
+        ));
+        let mut plain = super::Parser::with_capacity(1);
+        plain.add_argument(super::Argument::with_type("word").name("--level"));
+        let parsed = plain.try_parse_arguments(&["--level", &main]).unwrap();
+        assert_eq!(parsed.get_value("--level"), Some(&super::Value::from(main)));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
 }
@@ -0,0 +1,196 @@
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::ParseError;
+
+/// how deep response files may include other response files
+pub(crate) const MAX_DEPTH: usize = 16;
+
+/// the part of the token after the prefix, which has to be valid UTF-8
+pub(crate) fn strip_prefix<'a>(token: &'a OsStr, prefix: &str) -> Option<&'a OsStr> {
+    let rest = token.as_encoded_bytes().strip_prefix(prefix.as_bytes())?;
+    // SAFETY: the prefix is valid UTF-8, so the split is right after a UTF-8 substring
+    Some(unsafe { OsStr::from_encoded_bytes_unchecked(rest) })
+}
+
+/// replace every `@path` token with the tokens read from the file at `path`  
+/// `@@value` is kept as `@value` and nothing after the `--` terminator is expanded  
+/// relative paths inside a response file are relative to the directory of that file
+pub(crate) fn expand(arguments: Vec<OsString>) -> Result<Vec<OsString>, ParseError> {
+    let mut expander = Expander {
+        expanded: Vec::with_capacity(arguments.len()),
+        stack: Vec::new(),
+        terminated: false,
+    };
+    for argument in arguments {
+        expander.token(argument, None)?;
+    }
+    Ok(expander.expanded)
+}
+
+struct Expander {
+    expanded: Vec<OsString>,
+    /// canonical paths of the files being read, to detect files including themselves
+    stack: Vec<PathBuf>,
+    terminated: bool,
+}
+
+impl Expander {
+    /// `origin` is the file and line the token was read from, `None` for the command line
+    fn token(&mut self, token: OsString, origin: Option<(&Path, usize)>) -> Result<(), ParseError> {
+        if self.terminated || token == "--" {
+            self.terminated = true;
+            self.expanded.push(token);
+            return Ok(());
+        }
+        match strip_prefix(&token, "@") {
+            Some(rest) if strip_prefix(rest, "@").is_some() => {
+                self.expanded.push(rest.to_os_string());
+                Ok(())
+            }
+            Some(rest) if !rest.is_empty() => self.include(Path::new(rest), origin),
+            _ => {
+                self.expanded.push(token);
+                Ok(())
+            }
+        }
+    }
+    fn include(&mut self, path: &Path, origin: Option<(&Path, usize)>) -> Result<(), ParseError> {
+        let path = match origin {
+            Some((parent, _)) if path.is_relative() => {
+                parent.parent().unwrap_or_else(|| Path::new("")).join(path)
+            }
+            _ => path.to_path_buf(),
+        };
+        let error = |message: String| match origin {
+            Some((parent, line)) => ParseError::ResponseFile {
+                path: parent.to_path_buf(),
+                line,
+                message,
+            },
+            None => ParseError::ResponseFileRead {
+                path: path.clone(),
+                message,
+            },
+        };
+        if self.stack.len() >= MAX_DEPTH {
+            return Err(error(format!(
+                "response files nested more than {} deep",
+                MAX_DEPTH
+            )));
+        }
+        let text = std::fs::read_to_string(&path)
+            .and_then(|text| Ok((std::fs::canonicalize(&path)?, text)));
+        let (canonical, text) = match (text, origin) {
+            (Ok(read), _) => read,
+            (Err(f), Some(_)) => {
+                return Err(error(format!("cannot read {}: {}", path.display(), f)))
+            }
+            (Err(f), None) => return Err(error(f.to_string())),
+        };
+        if self.stack.contains(&canonical) {
+            return Err(error(format!("{} includes itself", path.display())));
+        }
+        let tokens = tokenize(&text).map_err(|(line, message)| ParseError::ResponseFile {
+            path: path.clone(),
+            line,
+            message,
+        })?;
+        self.stack.push(canonical);
+        for (token, line) in tokens {
+            self.token(OsString::from(token), Some((&path, line)))?;
+        }
+        self.stack.pop();
+        Ok(())
+    }
+}
+
+/// split the text of a response file into tokens, each with the line it starts on  
+/// tokens are separated by whitespace, `#` at the start of a token comments out the rest of the line,
+/// single quotes keep everything, double quotes allow `\"` and `\\`, a backslash outside quotes
+/// keeps the next character  
+/// the error is the line of the unterminated quote with a message
+fn tokenize(text: &str) -> Result<Vec<(String, usize)>, (usize, String)> {
+    let mut tokens = Vec::new();
+    let mut token: Option<(String, usize)> = None;
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' | ' ' | '\t' | '\r' => {
+                tokens.extend(token.take());
+                if c == '\n' {
+                    line += 1;
+                }
+            }
+            '#' if token.is_none() => {
+                while chars.peek().is_some_and(|f| *f != '\n') {
+                    chars.next();
+                }
+            }
+            '\'' | '"' => {
+                let start = line;
+                let current = &mut token.get_or_insert_with(|| (String::new(), line)).0;
+                loop {
+                    match chars.next() {
+                        None => return Err((start, "unterminated quote".to_string())),
+                        Some(f) if f == c => break,
+                        Some('\\') if c == '"' && matches!(chars.peek(), Some('"' | '\\')) => {
+                            current.push(chars.next().unwrap());
+                        }
+                        Some(f) => {
+                            if f == '\n' {
+                                line += 1;
+                            }
+                            current.push(f);
+                        }
+                    }
+                }
+            }
+            '\\' => {
+                let current = &mut token.get_or_insert_with(|| (String::new(), line)).0;
+                match chars.next() {
+                    Some('\n') => line += 1,
+                    Some(f) => current.push(f),
+                    None => current.push('\\'),
+                }
+            }
+            _ => token.get_or_insert_with(|| (String::new(), line)).0.push(c),
+        }
+    }
+    tokens.extend(token);
+    Ok(tokens)
+}
+
+mod response_tests {
+    #[test]
+    fn tokenizing() {
+        let text = "\
+--level debug # comment until the end of the line
+# a whole line comment
+'single \"quoted\"' \"double \\\"quoted\\\" \\\\\" ''
+with\\ space a#b
+";
+        let tokens = super::tokenize(text).unwrap();
+        let expected = vec![
+            ("--level", 1),
+            ("debug", 1),
+            ("single \"quoted\"", 3),
+            ("double \"quoted\" \\", 3),
+            ("", 3),
+            ("with space", 4),
+            ("a#b", 4),
+        ];
+        let tokens = tokens
+            .iter()
+            .map(|(token, line)| (token.as_str(), *line))
+            .collect::<Vec<_>>();
+        assert_eq!(tokens, expected);
+        assert_eq!(
+            super::tokenize("a\n'b\nc"),
+            Err((2, "unterminated quote".to_string()))
+        );
+    }
+}
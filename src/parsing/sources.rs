@@ -0,0 +1,32 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// where the value of a parsed argument came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// the command line, `index` is the position of the token in the arguments given,
+    /// counting the program name when they come from `parse_env`  
+    /// response files are expanded first, the arguments read from a file take the place
+    /// of the `@file` token and shift the indices after it  
+    /// for arguments given several times this is the last occurrence
+    CommandLine { index: usize },
+    /// the environment variable of the argument
+    Environment { variable: String },
+    /// a line of the configuration file
+    ConfigFile { path: PathBuf, line: usize },
+    /// the default value of the argument
+    Default,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::CommandLine { index } => write!(f, "command line argument {}", index),
+            ValueSource::Environment { variable } => {
+                write!(f, "environment variable {}", variable)
+            }
+            ValueSource::ConfigFile { path, line } => write!(f, "{}:{}", path.display(), line),
+            ValueSource::Default => write!(f, "default"),
+        }
+    }
+}
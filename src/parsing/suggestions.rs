@@ -0,0 +1,77 @@
+/// most suggestions given for a single mistyped word
+const MAX_SUGGESTIONS: usize = 3;
+
+/// the number of single character insertions, deletions, substitutions and swaps
+/// of neighbouring characters needed to turn one word into the other
+pub(crate) fn edit_distance(first: &str, second: &str) -> usize {
+    let first = first.chars().collect::<Vec<_>>();
+    let second = second.chars().collect::<Vec<_>>();
+    let mut table = vec![vec![0; second.len() + 1]; first.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=first.len() {
+        for j in 1..=second.len() {
+            let cost = usize::from(first[i - 1] != second[j - 1]);
+            let mut distance = (table[i - 1][j - 1] + cost)
+                .min(table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1);
+            if i > 1 && j > 1 && first[i - 1] == second[j - 2] && first[i - 2] == second[j - 1] {
+                distance = distance.min(table[i - 2][j - 2] + 1);
+            }
+            table[i][j] = distance;
+        }
+    }
+    table[first.len()][second.len()]
+}
+
+/// the candidates close enough to the word to be what was meant, closest first  
+/// a candidate is close enough when a third of the word or less has to be changed,
+/// leading dashes do not count so single letter options get no suggestions
+pub(crate) fn suggest<'a, I>(word: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let limit = word.trim_start_matches('-').chars().count() / 3;
+    let mut close = candidates
+        .into_iter()
+        .filter(|f| *f != word)
+        .map(|f| (edit_distance(word, f), f))
+        .filter(|f| f.0 <= limit)
+        .collect::<Vec<_>>();
+    close.sort_unstable();
+    close.dedup();
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|f| f.1.to_string())
+        .collect()
+}
+
+mod suggestions_tests {
+    #[test]
+    fn distance() {
+        assert_eq!(super::edit_distance("", "abc"), 3);
+        assert_eq!(super::edit_distance("kitten", "sitting"), 3);
+        assert_eq!(super::edit_distance("--dragon-color", "--dragon-colors"), 1);
+        assert_eq!(super::edit_distance("same", "same"), 0);
+        assert_eq!(super::edit_distance("biuld", "build"), 1);
+    }
+    #[test]
+    fn ranking() {
+        let candidates = ["--dragon-colors", "--dragon-count", "--sleep", "--no-sleep"];
+        assert_eq!(
+            super::suggest("--dragon-color", candidates.iter().copied()),
+            vec!["--dragon-colors", "--dragon-count"]
+        );
+        assert_eq!(
+            super::suggest("--slep", candidates.iter().copied()),
+            vec!["--sleep"]
+        );
+        assert!(super::suggest("--verbose", candidates.iter().copied()).is_empty());
+        assert!(super::suggest("-s", ["-v", "-q"].iter().copied()).is_empty());
+    }
+}
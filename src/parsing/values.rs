@@ -3,6 +3,7 @@ pub enum Value {
     Flag(bool),
     Word(String),
     Vector(Vec<String>),
+    Count(usize),
 }
 
 impl Default for Value {
@@ -11,12 +12,50 @@ impl Default for Value {
     }
 }
 
+impl Value {
+    /// name of the variant: "flag" "word" "vector" or "count"
+    pub fn kind(&self) -> &str {
+        match self {
+            Value::Flag(_) => "flag",
+            Value::Word(_) => "word",
+            Value::Vector(_) => "vector",
+            Value::Count(_) => "count",
+        }
+    }
+    /// the value as a list of strings, vectors are returned as they are
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            Value::Vector(vector) => vector,
+            Value::Word(word) => vec![word],
+            value => vec![value.to_string()],
+        }
+    }
+}
+
+/// flags show as "true" or "false", vectors are separated by commas
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Flag(flag) => write!(f, "{}", flag),
+            Value::Word(word) => write!(f, "{}", word),
+            Value::Vector(vector) => write!(f, "{}", vector.join(",")),
+            Value::Count(count) => write!(f, "{}", count),
+        }
+    }
+}
+
 impl From<bool> for Value {
     fn from(s: bool) -> Value {
         Value::Flag(s)
     }
 }
 
+impl From<usize> for Value {
+    fn from(s: usize) -> Value {
+        Value::Count(s)
+    }
+}
+
 impl From<String> for Value {
     fn from(s: String) -> Value {
         Value::Word(s)
@@ -57,11 +96,7 @@ impl From<&[String]> for Value {
 
 impl From<&[&str]> for Value {
     fn from(s: &[&str]) -> Value {
-        let new_s = s
-            .to_vec()
-            .into_iter()
-            .map(str::to_string)
-            .collect::<Vec<_>>();
+        let new_s = s.iter().map(|f| f.to_string()).collect::<Vec<_>>();
         Value::Vector(new_s)
     }
 }
@@ -73,6 +108,11 @@ mod values_tests {
         assert!(matches!(truthful, super::Value::Flag(false)));
     }
     #[test]
+    fn from_usize() {
+        let verbosity = super::Value::from(3usize);
+        assert!(matches!(verbosity, super::Value::Count(3)));
+    }
+    #[test]
     fn from_str() {
         let pi = super::Value::from("3.14");
         assert!(matches!(pi, super::Value::Word(_)));
@@ -107,6 +147,11 @@ mod values_tests {
         assert!(matches!(fruit_value, super::Value::Vector(_)));
     }
     #[test]
+    fn display() {
+        assert_eq!(super::Value::from(true).to_string(), "true");
+        assert_eq!(super::Value::from(vec!["a", "b"]).to_string(), "a,b");
+    }
+    #[test]
     fn from_slice_str() {
         let tv_shows = ["mista robot", "jack ryan", "flapjack bryan"];
         let tv_shows_value = super::Value::from(&tv_shows[1..]);
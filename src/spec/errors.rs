@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+
+/// every way in which reading a parser from a spec can fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecError {
+    /// the text is not valid JSON, lines and columns start at 1
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// a field is missing, unknown or holds the wrong kind of value, or the parser it describes
+    /// is invalid, `field` is the path to it, e.g. `subcommands[0].arguments[2].type`
+    Invalid { field: String, message: String },
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecError::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            SpecError::Invalid { field, message } => write!(f, "{}: {}", field, message),
+        }
+    }
+}
+
+impl Error for SpecError {}
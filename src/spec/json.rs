@@ -0,0 +1,355 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::SpecError;
+
+/// how deep arrays and objects may be nested
+pub(crate) const MAX_DEPTH: usize = 128;
+
+/// the JSON values a spec is made of, numbers are limited to whole non negative numbers
+/// and objects keep the order of their fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// name of the kind of value, used in error messages
+    pub(crate) fn kind(&self) -> &str {
+        match self {
+            Json::Null => "null",
+            Json::Bool(_) => "boolean",
+            Json::Number(_) => "number",
+            Json::String(_) => "string",
+            Json::Array(_) => "array",
+            Json::Object(_) => "object",
+        }
+    }
+    pub(crate) fn parse(text: &str) -> Result<Json, SpecError> {
+        let mut reader = Reader {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+            depth: 0,
+        };
+        let value = reader.value()?;
+        reader.whitespace();
+        if reader.chars.peek().is_some() {
+            return Err(reader.error("unexpected text after the value"));
+        }
+        Ok(value)
+    }
+    /// the value indented by two spaces, arrays without objects or arrays inside stay on one line
+    pub(crate) fn render(&self) -> String {
+        let mut text = String::new();
+        self.render_into(&mut text, 0);
+        text.push('\n');
+        text
+    }
+    fn render_into(&self, text: &mut String, indent: usize) {
+        match self {
+            Json::Null => text.push_str("null"),
+            Json::Bool(value) => text.push_str(&value.to_string()),
+            Json::Number(value) => text.push_str(&value.to_string()),
+            Json::String(value) => render_string(text, value),
+            Json::Array(values) if values.is_empty() => text.push_str("[]"),
+            Json::Array(values)
+                if !values
+                    .iter()
+                    .any(|f| matches!(f, Json::Array(_) | Json::Object(_))) =>
+            {
+                text.push('[');
+                for (position, value) in values.iter().enumerate() {
+                    if position > 0 {
+                        text.push_str(", ");
+                    }
+                    value.render_into(text, indent);
+                }
+                text.push(']');
+            }
+            Json::Array(values) => {
+                text.push_str("[\n");
+                for (position, value) in values.iter().enumerate() {
+                    text.push_str(&" ".repeat(indent + 2));
+                    value.render_into(text, indent + 2);
+                    if position + 1 < values.len() {
+                        text.push(',');
+                    }
+                    text.push('\n');
+                }
+                text.push_str(&" ".repeat(indent));
+                text.push(']');
+            }
+            Json::Object(fields) if fields.is_empty() => text.push_str("{}"),
+            Json::Object(fields) => {
+                text.push_str("{\n");
+                for (position, (key, value)) in fields.iter().enumerate() {
+                    text.push_str(&" ".repeat(indent + 2));
+                    render_string(text, key);
+                    text.push_str(": ");
+                    value.render_into(text, indent + 2);
+                    if position + 1 < fields.len() {
+                        text.push(',');
+                    }
+                    text.push('\n');
+                }
+                text.push_str(&" ".repeat(indent));
+                text.push('}');
+            }
+        }
+    }
+}
+
+fn render_string(text: &mut String, value: &str) {
+    text.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            c if c.is_control() => text.push_str(&format!("\\u{:04x}", c as u32)),
+            c => text.push(c),
+        }
+    }
+    text.push('"');
+}
+
+struct Reader<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn error(&self, message: &str) -> SpecError {
+        SpecError::Syntax {
+            line: self.line,
+            column: self.column,
+            message: message.to_string(),
+        }
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+    fn whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|f| f.is_ascii_whitespace()) {
+            self.next();
+        }
+    }
+    fn expect(&mut self, expected: char) -> Result<(), SpecError> {
+        self.whitespace();
+        if self.chars.peek() == Some(&expected) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {}", expected)))
+        }
+    }
+    fn value(&mut self) -> Result<Json, SpecError> {
+        self.whitespace();
+        match self.chars.peek().copied() {
+            Some('{' | '[') if self.depth >= MAX_DEPTH => Err(self.error("nesting too deep")),
+            Some(c @ ('{' | '[')) => {
+                self.depth += 1;
+                let value = if c == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => self.string().map(Json::String),
+            Some('0'..='9') => self.number(),
+            Some('-') => Err(self.error("only whole non negative numbers are supported")),
+            Some(c) if c.is_ascii_alphabetic() => self.literal(),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of text")),
+        }
+    }
+    fn literal(&mut self) -> Result<Json, SpecError> {
+        let error = self.error("expected true, false or null");
+        let mut word = String::new();
+        while self.chars.peek().is_some_and(|f| f.is_ascii_alphabetic()) {
+            word.push(self.next().unwrap());
+        }
+        match word.as_str() {
+            "true" => Ok(Json::Bool(true)),
+            "false" => Ok(Json::Bool(false)),
+            "null" => Ok(Json::Null),
+            _ => Err(error),
+        }
+    }
+    fn number(&mut self) -> Result<Json, SpecError> {
+        let error = self.error("number is too large");
+        let mut digits = String::new();
+        while self.chars.peek().is_some_and(|f| f.is_ascii_digit()) {
+            digits.push(self.next().unwrap());
+        }
+        if matches!(self.chars.peek(), Some('.' | 'e' | 'E')) {
+            return Err(self.error("only whole non negative numbers are supported"));
+        }
+        digits.parse().map(Json::Number).map_err(|_| error)
+    }
+    fn string(&mut self) -> Result<String, SpecError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(value),
+                Some('\\') => {
+                    let c = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    value.push(c);
+                }
+                Some(c) if c.is_control() => return Err(self.error("control character in string")),
+                Some(c) => value.push(c),
+            }
+        }
+    }
+    /// the character of a `\u` escape, joining surrogate pairs
+    fn unicode(&mut self) -> Result<char, SpecError> {
+        let high = self.hex()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(self.error("unpaired surrogate"));
+            }
+            let low = self.hex()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+    fn hex(&mut self) -> Result<u32, SpecError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.next().and_then(|f| f.to_digit(16));
+            match digit {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(self.error("invalid unicode escape")),
+            }
+        }
+        Ok(code)
+    }
+    fn array(&mut self) -> Result<Json, SpecError> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        self.whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(values)),
+                _ => return Err(self.error("expected , or ]")),
+            }
+        }
+    }
+    fn object(&mut self) -> Result<Json, SpecError> {
+        self.expect('{')?;
+        let mut fields = Vec::<(String, Json)>::new();
+        self.whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.whitespace();
+            let duplicate = self.error("duplicate key");
+            let key = self.string()?;
+            if fields.iter().any(|f| f.0 == key) {
+                return Err(duplicate);
+            }
+            self.expect(':')?;
+            let value = self.value()?;
+            fields.push((key, value));
+            self.whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(fields)),
+                _ => return Err(self.error("expected , or }")),
+            }
+        }
+    }
+}
+
+mod json_tests {
+    #[test]
+    fn round_trip() {
+        let text =
+            "{\"a\": [1, true, null], \"b\": {\"c\": \"x\\\"\\u00e9\\ud83d\\ude00\"}, \"d\": []}";
+        let json = super::Json::parse(text).unwrap();
+        let rendered = json.render();
+        let expected = "\
+{
+  \"a\": [1, true, null],
+  \"b\": {
+    \"c\": \"x\\\"é😀\"
+  },
+  \"d\": []
+}
+";
+        assert_eq!(rendered, expected);
+        assert_eq!(super::Json::parse(&rendered).unwrap(), json);
+    }
+    #[test]
+    fn syntax_errors() {
+        let error = super::Json::parse("{\n  \"a\": 1,\n  \"a\": 2\n}").unwrap_err();
+        assert_eq!(error.to_string(), "line 3, column 3: duplicate key");
+        let error = super::Json::parse("[1.5]").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 3: only whole non negative numbers are supported"
+        );
+        assert!(super::Json::parse("{\"a\" 1}").is_err());
+        assert!(super::Json::parse("[1] 2").is_err());
+        assert!(super::Json::parse("\"open").is_err());
+    }
+    #[test]
+    fn nesting() {
+        let depth = super::MAX_DEPTH;
+        let nested = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(super::Json::parse(&nested).is_ok());
+        let error = super::Json::parse(&"[".repeat(200000)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("line 1, column {}: nesting too deep", depth + 1)
+        );
+        let error = crate::Parser::from_spec_str(&"{\"a\": ".repeat(200000)).unwrap_err();
+        assert!(matches!(error, crate::SpecError::Syntax { .. }));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod errors;
+pub(crate) mod json;
+pub mod spec_file;
@@ -0,0 +1,588 @@
+use std::convert::TryFrom;
+
+use crate::spec::json::Json;
+use crate::ArgGroup;
+use crate::Argument;
+use crate::Arity;
+use crate::Parser;
+use crate::RepeatPolicy;
+use crate::SpecError;
+use crate::Value;
+
+const PARSER_FIELDS: &[&str] = &[
+    "program",
+    "about",
+    "help_width",
+    "env_prefix",
+    "env_separator",
+    "allow_abbreviations",
+    "response_files",
+    "arguments",
+    "groups",
+    "subcommands",
+];
+const ARGUMENT_FIELDS: &[&str] = &[
+    "name",
+    "type",
+    "invocators",
+    "required",
+    "index",
+    "arity",
+    "help",
+    "value_name",
+    "default",
+    "env",
+    "possible_values",
+    "on_repeat",
+    "conflicts_with",
+    "requires",
+    "required_if",
+    "required_unless",
+];
+const GROUP_FIELDS: &[&str] = &["name", "arguments", "exclusive", "required"];
+
+impl Parser {
+    /// the whole parser as JSON, including its groups and subcommands  
+    /// fields holding their default value are left out, validators cannot be written
+    /// and are lost, everything else comes back the same from `from_spec_str`
+    pub fn to_spec_string(&self) -> String {
+        Json::Object(parser_fields(self)).render()
+    }
+    /// read a parser from a spec as written by `to_spec_string`  
+    /// unknown fields are rejected, and so are definitions `add_argument`,
+    /// `add_group` or `add_subcommand` would panic on
+    pub fn from_spec_str(text: &str) -> Result<Parser, SpecError> {
+        let json = Json::parse(text)?;
+        let fields = Fields::new(&json, "spec", PARSER_FIELDS)?;
+        parser_from_fields(&fields)
+    }
+}
+
+fn strings<S: AsRef<str>>(values: &[S]) -> Json {
+    Json::Array(
+        values
+            .iter()
+            .map(|f| Json::String(f.as_ref().to_string()))
+            .collect(),
+    )
+}
+
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::Flag(flag) => Json::Bool(*flag),
+        Value::Word(word) => Json::String(word.clone()),
+        Value::Vector(vector) => strings(vector),
+        Value::Count(count) => Json::Number(*count as u64),
+    }
+}
+
+fn parser_fields(parser: &Parser) -> Vec<(String, Json)> {
+    let mut fields = Vec::new();
+    let mut push = |key: &str, value: Json| fields.push((key.to_string(), value));
+    if !parser.program.is_empty() {
+        push("program", Json::String(parser.program.clone()));
+    }
+    if !parser.about.is_empty() {
+        push("about", Json::String(parser.about.clone()));
+    }
+    if parser.help_width != 0 {
+        push("help_width", Json::Number(parser.help_width as u64));
+    }
+    if !parser.env_prefix.is_empty() {
+        push("env_prefix", Json::String(parser.env_prefix.clone()));
+    }
+    if let Some(separator) = parser.env_separator {
+        push("env_separator", Json::String(separator.to_string()));
+    }
+    if parser.abbreviations {
+        push("allow_abbreviations", Json::Bool(true));
+    }
+    if parser.response_files {
+        push("response_files", Json::Bool(true));
+    }
+    if !parser.order.is_empty() {
+        let arguments = parser
+            .order
+            .iter()
+            .map(|f| argument_to_json(&parser.arguments[f]))
+            .collect();
+        push("arguments", Json::Array(arguments));
+    }
+    if !parser.groups.is_empty() {
+        push(
+            "groups",
+            Json::Array(parser.groups.iter().map(group_to_json).collect()),
+        );
+    }
+    if !parser.subcommand_order.is_empty() {
+        let subcommands = parser
+            .subcommand_order
+            .iter()
+            .map(|name| {
+                let mut fields = parser_fields(&parser.subcommands[name]);
+                fields.retain(|f| f.0 != "program" || f.1 != Json::String(name.clone()));
+                fields.insert(0, ("name".to_string(), Json::String(name.clone())));
+                Json::Object(fields)
+            })
+            .collect();
+        push("subcommands", Json::Array(subcommands));
+    }
+    fields
+}
+
+fn argument_to_json(argument: &Argument) -> Json {
+    let mut fields = Vec::new();
+    let mut push = |key: &str, value: Json| fields.push((key.to_string(), value));
+    push("name", Json::String(argument.name.clone()));
+    push("type", Json::String(argument.get_type().to_string()));
+    let invocators = argument
+        .invocators()
+        .into_iter()
+        .filter(|f| *f != argument.name)
+        .collect::<Vec<_>>();
+    if !invocators.is_empty() {
+        push("invocators", strings(&invocators));
+    }
+    if argument.required {
+        push("required", Json::Bool(true));
+    }
+    if let Some(index) = argument.get_index() {
+        push("index", Json::Number(index as u64));
+    }
+    if argument.get_arity() != Arity::One {
+        push("arity", Json::String(argument.get_arity().to_string()));
+    }
+    if !argument.get_help().is_empty() {
+        push("help", Json::String(argument.get_help().to_string()));
+    }
+    let default_name = Argument::with_type(argument.get_type())
+        .name(&argument.name)
+        .get_value_name();
+    if argument.get_value_name() != default_name {
+        push("value_name", Json::String(argument.get_value_name()));
+    }
+    if let Some(default) = argument.get_default_value() {
+        push("default", value_to_json(default));
+    }
+    if let Some(env) = argument.get_env() {
+        push("env", Json::String(env.to_string()));
+    }
+    if !argument.get_possible_values().is_empty() {
+        push("possible_values", strings(argument.get_possible_values()));
+    }
+    if argument.get_repeat_policy() != RepeatPolicy::LastWins {
+        push(
+            "on_repeat",
+            Json::String(argument.get_repeat_policy().to_string()),
+        );
+    }
+    if !argument.get_conflicts().is_empty() {
+        push("conflicts_with", strings(argument.get_conflicts()));
+    }
+    if !argument.get_requires().is_empty() {
+        push("requires", strings(argument.get_requires()));
+    }
+    if !argument.get_required_if().is_empty() {
+        let conditions = argument
+            .get_required_if()
+            .iter()
+            .map(|(name, value)| {
+                Json::Object(vec![
+                    ("argument".to_string(), Json::String(name.clone())),
+                    ("value".to_string(), value_to_json(value)),
+                ])
+            })
+            .collect();
+        push("required_if", Json::Array(conditions));
+    }
+    if !argument.get_required_unless().is_empty() {
+        push("required_unless", strings(argument.get_required_unless()));
+    }
+    Json::Object(fields)
+}
+
+fn group_to_json(group: &ArgGroup) -> Json {
+    let mut fields = vec![
+        ("name".to_string(), Json::String(group.name.clone())),
+        ("arguments".to_string(), strings(group.get_arguments())),
+    ];
+    if group.is_exclusive() {
+        fields.push(("exclusive".to_string(), Json::Bool(true)));
+    }
+    if group.is_required() {
+        fields.push(("required".to_string(), Json::Bool(true)));
+    }
+    Json::Object(fields)
+}
+
+fn invalid(field: &str, message: impl Into<String>) -> SpecError {
+    SpecError::Invalid {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+/// the fields of a JSON object with the path leading to it, for error messages
+struct Fields<'a> {
+    fields: &'a [(String, Json)],
+    path: String,
+}
+
+impl<'a> Fields<'a> {
+    /// the object at `path`, which may only have the `known` fields
+    fn new(json: &'a Json, path: &str, known: &[&str]) -> Result<Fields<'a>, SpecError> {
+        let fields = match json {
+            Json::Object(fields) => fields,
+            other => {
+                let message = format!("expected an object, found {}", other.kind());
+                return Err(invalid(path, message));
+            }
+        };
+        let fields = Fields {
+            fields,
+            path: path.to_string(),
+        };
+        if let Some((key, _)) = fields
+            .fields
+            .iter()
+            .find(|f| !known.contains(&f.0.as_str()))
+        {
+            return Err(invalid(&fields.path(key), "unknown field"));
+        }
+        Ok(fields)
+    }
+    fn path(&self, key: &str) -> String {
+        format!("{}.{}", self.path, key)
+    }
+    fn get(&self, key: &str) -> Option<&'a Json> {
+        self.fields.iter().find(|f| f.0 == key).map(|f| &f.1)
+    }
+    fn wrong(&self, key: &str, expected: &str, found: &Json) -> SpecError {
+        let message = format!("expected {}, found {}", expected, found.kind());
+        invalid(&self.path(key), message)
+    }
+    fn string(&self, key: &str) -> Result<Option<&'a str>, SpecError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(Json::String(value)) => Ok(Some(value)),
+            Some(other) => Err(self.wrong(key, "a string", other)),
+        }
+    }
+    fn required_string(&self, key: &str) -> Result<&'a str, SpecError> {
+        self.string(key)?
+            .ok_or_else(|| invalid(&self.path(key), "missing field"))
+    }
+    fn flag(&self, key: &str) -> Result<bool, SpecError> {
+        match self.get(key) {
+            None => Ok(false),
+            Some(Json::Bool(value)) => Ok(*value),
+            Some(other) => Err(self.wrong(key, "a boolean", other)),
+        }
+    }
+    fn number(&self, key: &str) -> Result<Option<usize>, SpecError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(Json::Number(value)) => usize::try_from(*value)
+                .map(Some)
+                .map_err(|_| invalid(&self.path(key), "number is too large")),
+            Some(other) => Err(self.wrong(key, "a number", other)),
+        }
+    }
+    fn array(&self, key: &str) -> Result<&'a [Json], SpecError> {
+        match self.get(key) {
+            None => Ok(&[]),
+            Some(Json::Array(values)) => Ok(values),
+            Some(other) => Err(self.wrong(key, "an array", other)),
+        }
+    }
+    fn strings(&self, key: &str) -> Result<Vec<&'a str>, SpecError> {
+        let mut values = Vec::new();
+        for (position, value) in self.array(key)?.iter().enumerate() {
+            match value {
+                Json::String(value) => values.push(value.as_str()),
+                other => {
+                    let message = format!("expected a string, found {}", other.kind());
+                    return Err(invalid(
+                        &format!("{}[{}]", self.path(key), position),
+                        message,
+                    ));
+                }
+            }
+        }
+        Ok(values)
+    }
+    /// every element of the array field as an object with the `known` fields
+    fn objects(&self, key: &str, known: &[&str]) -> Result<Vec<Fields<'a>>, SpecError> {
+        self.array(key)?
+            .iter()
+            .enumerate()
+            .map(|(position, value)| {
+                Fields::new(value, &format!("{}[{}]", self.path(key), position), known)
+            })
+            .collect()
+    }
+}
+
+fn value_from_json(json: &Json, field: &str) -> Result<Value, SpecError> {
+    match json {
+        Json::Bool(flag) => Ok(Value::from(*flag)),
+        Json::String(word) => Ok(Value::from(word.as_str())),
+        Json::Number(count) => usize::try_from(*count)
+            .map(Value::from)
+            .map_err(|_| invalid(field, "number is too large")),
+        Json::Array(values) => values
+            .iter()
+            .map(|f| match f {
+                Json::String(value) => Ok(value.clone()),
+                other => Err(invalid(
+                    field,
+                    format!("expected a string, found {}", other.kind()),
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::from),
+        other => Err(invalid(
+            field,
+            format!("expected a value, found {}", other.kind()),
+        )),
+    }
+}
+
+fn parser_from_fields(fields: &Fields) -> Result<Parser, SpecError> {
+    let mut parser = Parser::new();
+    parser.program = fields.string("program")?.unwrap_or_default().to_string();
+    parser.about = fields.string("about")?.unwrap_or_default().to_string();
+    parser.help_width = fields.number("help_width")?.unwrap_or(0);
+    parser.env_prefix = fields.string("env_prefix")?.unwrap_or_default().to_string();
+    if let Some(separator) = fields.string("env_separator")? {
+        let mut chars = separator.chars();
+        match (chars.next(), chars.next()) {
+            (Some(separator), None) => parser.env_separator = Some(separator),
+            _ => {
+                let field = fields.path("env_separator");
+                return Err(invalid(&field, "expected a single character"));
+            }
+        }
+    }
+    parser.abbreviations = fields.flag("allow_abbreviations")?;
+    parser.response_files = fields.flag("response_files")?;
+    for argument in fields.objects("arguments", ARGUMENT_FIELDS)? {
+        let built = argument_from_fields(&argument)?;
+        parser
+            .check_argument(&built)
+            .map_err(|f| invalid(&argument.path, f))?;
+        parser.add_argument(built);
+    }
+    for group in fields.objects("groups", GROUP_FIELDS)? {
+        let mut built =
+            ArgGroup::new(group.required_string("name")?).args(&group.strings("arguments")?);
+        if group.flag("exclusive")? {
+            built = built.exclusive();
+        }
+        if group.flag("required")? {
+            built = built.required();
+        }
+        parser
+            .check_group(&built)
+            .map_err(|f| invalid(&group.path, f))?;
+        parser.add_group(built);
+    }
+    let mut known = PARSER_FIELDS.to_vec();
+    known.push("name");
+    for subcommand in fields.objects("subcommands", &known)? {
+        let name = subcommand.required_string("name")?;
+        let built = parser_from_fields(&subcommand)?;
+        parser
+            .check_subcommand(name)
+            .map_err(|f| invalid(&subcommand.path, f))?;
+        parser.add_subcommand(name, built);
+    }
+    Ok(parser)
+}
+
+fn argument_from_fields(fields: &Fields) -> Result<Argument, SpecError> {
+    let argument_type = fields.required_string("type")?;
+    if !["flag", "word", "vector", "positional", "count"].contains(&argument_type) {
+        let message = format!("unknown type: {}", argument_type);
+        return Err(invalid(&fields.path("type"), message));
+    }
+    let mut argument = Argument::with_type(argument_type)
+        .name(fields.required_string("name")?)
+        .required(fields.flag("required")?);
+    for invocator in fields.strings("invocators")? {
+        argument = argument.invoke_with(invocator);
+    }
+    if let Some(index) = fields.number("index")? {
+        argument = argument.index(index);
+    }
+    if let Some(arity) = fields.string("arity")? {
+        let arity = match arity {
+            "one" => Arity::One,
+            "optional" => Arity::Optional,
+            "one-or-more" => Arity::OneOrMore,
+            _ => {
+                let message = format!("unknown arity: {}", arity);
+                return Err(invalid(&fields.path("arity"), message));
+            }
+        };
+        argument = argument.arity(arity);
+    }
+    if let Some(help) = fields.string("help")? {
+        argument = argument.help(help);
+    }
+    if let Some(value_name) = fields.string("value_name")? {
+        argument = argument.value_name(value_name);
+    }
+    if let Some(default) = fields.get("default") {
+        argument = argument.default_value(value_from_json(default, &fields.path("default"))?);
+    }
+    if let Some(env) = fields.string("env")? {
+        argument = argument.env(env);
+    }
+    let possible_values = fields.strings("possible_values")?;
+    if !possible_values.is_empty() {
+        argument = argument.possible_values(&possible_values);
+    }
+    if let Some(policy) = fields.string("on_repeat")? {
+        let policy = match policy {
+            "last-wins" => RepeatPolicy::LastWins,
+            "append" => RepeatPolicy::Append,
+            "error" => RepeatPolicy::Error,
+            _ => {
+                let message = format!("unknown repeat policy: {}", policy);
+                return Err(invalid(&fields.path("on_repeat"), message));
+            }
+        };
+        argument = argument.on_repeat(policy);
+    }
+    for name in fields.strings("conflicts_with")? {
+        argument = argument.conflicts_with(name);
+    }
+    for name in fields.strings("requires")? {
+        argument = argument.requires(name);
+    }
+    for condition in fields.objects("required_if", &["argument", "value"])? {
+        let name = condition.required_string("argument")?;
+        let value = match condition.get("value") {
+            Some(value) => value_from_json(value, &condition.path("value"))?,
+            None => return Err(invalid(&condition.path("value"), "missing field")),
+        };
+        argument = argument.required_if(name, value);
+    }
+    for name in fields.strings("required_unless")? {
+        argument = argument.required_unless(name);
+    }
+    Ok(argument)
+}
+
+mod spec_file_tests {
+    #[test]
+    fn round_trip() {
+        let mut migrate = super::Parser::new().about("run the migrations");
+        migrate.add_argument(super::Argument::with_type("flag").name("--dry-run"));
+        let mut parser = super::Parser::with_capacity(6)
+            .program_name("dragons")
+            .about("keeps track of \"dragons\"")
+            .help_width(60)
+            .env_prefix("DRAGONS")
+            .env_separator(':')
+            .allow_abbreviations(true);
+        parser.add_argument(
+            super::Argument::with_type("vector")
+                .name("colors")
+                .invoke_with("-c")
+                .invoke_with("--colors")
+                .value_name("COLOR")
+                .possible_values(&["red", "green"])
+                .default_value(vec!["red"]),
+        );
+        parser.add_argument(
+            super::Argument::with_type("count")
+                .name("-v")
+                .default_value(1usize)
+                .help("more output"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("word")
+                .name("--tag")
+                .on_repeat(super::RepeatPolicy::Append)
+                .env("TAGS"),
+        );
+        parser.add_argument(
+            super::Argument::with_type("flag")
+                .name("--json")
+                .conflicts_with("-v")
+                .required_if("colors", vec!["green"]),
+        );
+        parser.add_argument(super::Argument::with_type("flag").name("--yaml"));
+        parser.add_argument(
+            super::Argument::with_type("positional")
+                .name("caves")
+                .arity(super::Arity::OneOrMore),
+        );
+        parser.add_group(
+            super::ArgGroup::new("output")
+                .args(&["--json", "--yaml"])
+                .exclusive(),
+        );
+        parser.add_subcommand("migrate", migrate);
+        let spec = parser.to_spec_string();
+        let read = super::Parser::from_spec_str(&spec).unwrap();
+        assert_eq!(read.to_spec_string(), spec);
+        assert_eq!(read.render_help(), parser.render_help());
+        let arguments = [
+            "--col",
+            "red",
+            "--yaml",
+            "-vv",
+            "deep",
+            "migrate",
+            "--dry-run",
+        ];
+        let env = std::collections::HashMap::new();
+        let expected = parser
+            .try_parse_arguments_with_env(&arguments, &env)
+            .unwrap();
+        let parsed = read.try_parse_arguments_with_env(&arguments, &env).unwrap();
+        assert_eq!(parsed.arguments, expected.arguments);
+        assert_eq!(
+            parsed.subcommand().unwrap().1.arguments,
+            expected.subcommand().unwrap().1.arguments
+        );
+        assert!(spec.contains("\"invocators\": [\"-c\", \"--colors\"]"));
+        assert!(spec.contains("\"default\": 1"));
+        assert!(spec.contains("\"name\": \"migrate\",\n      \"about\": \"run the migrations\""));
+    }
+    #[test]
+    fn invalid_specs() {
+        let error = super::Parser::from_spec_str(
+            "{\"arguments\": [{\"name\": \"a\", \"type\": \"list\"}]}",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "spec.arguments[0].type: unknown type: list"
+        );
+        let error =
+            super::Parser::from_spec_str("{\"program\": \"x\", \"colour\": true}").unwrap_err();
+        assert_eq!(error.to_string(), "spec.colour: unknown field");
+        let spec = "{\"arguments\": [
+            {\"name\": \"--a\", \"type\": \"flag\"},
+            {\"name\": \"--b\", \"type\": \"flag\", \"invocators\": [\"--a\"]}
+        ]}";
+        assert_eq!(
+            super::Parser::from_spec_str(spec).unwrap_err(),
+            super::SpecError::Invalid {
+                field: "spec.arguments[1]".to_string(),
+                message: "duplicate invocator found: --a".to_string()
+            }
+        );
+        let error =
+            super::Parser::from_spec_str("{\"subcommands\": [{\"about\": 3}]}").unwrap_err();
+        assert_eq!(error.to_string(), "spec.subcommands[0].name: missing field");
+        assert!(matches!(
+            super::Parser::from_spec_str("{"),
+            Err(super::SpecError::Syntax { .. })
+        ));
+    }
+}